@@ -1,20 +1,58 @@
-use gloo::file::{Blob, ObjectUrl};
+use gloo::{
+    file::{futures::read_as_text, Blob, File, ObjectUrl},
+    timers::callback::Interval,
+};
+use js_sys::Date;
 use leptos::{
-    component, create_effect, create_rw_signal, document, ev::MouseEvent, event_target_value,
-    provide_context, spawn_local, view, window, IntoView, RwSignal, SignalGet, SignalSet,
+    batch, component, create_effect, create_memo, create_node_ref, create_rw_signal, document,
+    ev::{Event, MouseEvent},
+    event_target, event_target_value,
+    html::{Div, Input},
+    provide_context, spawn_local, use_context, view, window, IntoView, RwSignal, Signal, SignalGet,
+    SignalGetUntracked, SignalSet, SignalUpdate, SignalWith,
 };
 use regex::Regex;
 use serde::{Deserialize, Serialize};
-use serde_json::json;
-use std::{collections::HashMap, sync::LazyLock};
+use std::{collections::HashMap, ops::Range, sync::LazyLock, time::Duration};
 use wasm_bindgen::JsCast;
-use web_sys::HtmlElement;
-
-use crate::components::{StatisticsOptionsPanel, ToggleSwitch};
+use web_sys::{HtmlElement, HtmlTextAreaElement};
+
+use crate::{
+    characters::{CharacterClass, CharacterExclusions, CharacterUnit},
+    complexity::ReadingEstimate,
+    components::{
+        CharacterFrequencyPanel, CompareDialog, DocumentLibrary, DuplicatePanel, EditorHighlights,
+        HistoryDialog, OutlinePanel, ParagraphPanel, QuotePanel, RepetitionPanel, ScriptPanel,
+        SentencePanel, SpeedProfileEditor, StatisticsOptionsPanel, SubtitlesPanel, Teleprompter,
+        ToggleSwitch, TranslationDialog, UndoToast, WritingActivityPanel,
+    },
+    documents::{self, Document, DocumentSettings},
+    duplicates::{self, DuplicateGroup, DuplicateOptions},
+    editor::EDITOR_ID,
+    goals::{Goal, GoalMetric},
+    history::{self, Revision},
+    markup::{self, MarkupOptions, SourceFormat},
+    outline::{self, HeadingPattern, Section},
+    paragraphs::{self, ParagraphMetrics},
+    platforms::Platform,
+    quote::{DiscountGrid, QuoteFile},
+    repetition::{self, Repetition, RepetitionOptions},
+    script::{self, SlideTiming},
+    sentences::{self, SentenceMetrics},
+    sessions::{self, WritingLog},
+    sms::{self, SmsAnalysis},
+    speed::{Activity, SpeedProfiles},
+    storage::Storage,
+    subtitles::{self, Cue, ReadingSpeedLimits},
+    translation::Translation,
+    undo::{self, UndoEntry},
+};
 
-static WORD_REGEX: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"\w+").unwrap());
-static SENTENCE_REGEX: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"(?i)[^.!?]+[.!?]").unwrap());
-static PARAGRAPH_REGEX: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"\n\s*\n").unwrap());
+pub(crate) static WORD_REGEX: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"\w+").unwrap());
+pub(crate) static SENTENCE_REGEX: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"(?i)[^.!?]+[.!?]").unwrap());
+pub(crate) static PARAGRAPH_REGEX: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"\n\s*\n").unwrap());
 
 #[repr(usize)]
 #[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, PartialOrd, Ord)]
@@ -29,11 +67,73 @@ pub enum StatisticOption {
     AvgSentenceWords,
     AvgSentenceChars,
     AvgWordLength,
-    ReadingTime(usize),
-    SpeakingTime(usize),
-    HandWritingTime(usize),
+    ReadingTime,
+    SpeakingTime,
+    HandWritingTime,
     UniqueWords,
     Words,
+    SubtitleWordsPerMinute,
+    SubtitleCharactersPerSecond,
+    SmsSegments,
+    Graphemes,
+    CodePoints,
+    Utf8Bytes,
+    Utf16Units,
+    VisibleWidth,
+    Letters,
+    Digits,
+    PunctuationMarks,
+    UppercaseLetters,
+    LowercaseLetters,
+    Emoji,
+    Duplicates,
+}
+
+impl StatisticOption {
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::Characters => "Characters",
+            Self::CharacterCountNoSpaces => "Character Count (No Spaces)",
+            Self::LineCount => "Line Count",
+            Self::Paragraphs => "Paragraphs",
+            Self::Sentences => "Sentences",
+            Self::LongestSentenceWords => "Longest Sentence (Words)",
+            Self::ShortestSentenceWords => "Shortest Sentence (Words)",
+            Self::AvgSentenceWords => "Avg. Sentence (Words)",
+            Self::AvgSentenceChars => "Avg. Sentence (Characters)",
+            Self::AvgWordLength => "Avg. Word Length",
+            Self::ReadingTime => "Reading Time",
+            Self::SpeakingTime => "Speaking Time",
+            Self::HandWritingTime => "Hand Writing Time",
+            Self::UniqueWords => "Unique Words",
+            Self::Words => "Words",
+            Self::SubtitleWordsPerMinute => "Subtitle WPM",
+            Self::SubtitleCharactersPerSecond => "Subtitle CPS",
+            Self::SmsSegments => "SMS Segments",
+            Self::Graphemes => CharacterUnit::Graphemes.label(),
+            Self::CodePoints => CharacterUnit::CodePoints.label(),
+            Self::Utf8Bytes => CharacterUnit::Utf8Bytes.label(),
+            Self::Utf16Units => CharacterUnit::Utf16Units.label(),
+            Self::VisibleWidth => CharacterUnit::VisibleWidth.label(),
+            Self::Letters => "Letters",
+            Self::Digits => "Digits",
+            Self::PunctuationMarks => "Punctuation Marks",
+            Self::UppercaseLetters => "Uppercase Letters",
+            Self::LowercaseLetters => "Lowercase Letters",
+            Self::Emoji => "Emoji",
+            Self::Duplicates => "Duplicates",
+        }
+    }
+
+    /// Whether the statistic depends on the text alone. The subtitle rates
+    /// come from the open document's subtitles, so a compared text would
+    /// show the same values.
+    pub fn is_comparable(self) -> bool {
+        !matches!(
+            self,
+            Self::SubtitleWordsPerMinute | Self::SubtitleCharactersPerSecond
+        )
+    }
 }
 
 #[component]
@@ -44,7 +144,7 @@ pub fn options_dialog<F>(
 where
     F: Fn(MouseEvent) + 'static,
 {
-    let storage = window().local_storage().unwrap().unwrap();
+    let state = use_context::<GlobalState>().unwrap_or_default();
     let show_words = create_rw_signal(statistics_options.get().contains(&StatisticOption::Words));
 
     let show_unique_words = create_rw_signal(
@@ -113,71 +213,185 @@ where
             .contains(&StatisticOption::LineCount),
     );
 
-    let reading_time = create_rw_signal(
-        storage
-            .get_item("reading_time")
-            .unwrap()
-            .unwrap_or_else(|| String::from("275"))
-            .parse::<usize>()
-            .unwrap_or_default(),
+    let show_subtitle_words_per_minute = create_rw_signal(
+        statistics_options
+            .get()
+            .contains(&StatisticOption::SubtitleWordsPerMinute),
     );
 
-    let show_reading_time = create_rw_signal(
+    let show_graphemes = create_rw_signal(
         statistics_options
             .get()
-            .contains(&StatisticOption::ReadingTime(reading_time.get())),
+            .contains(&StatisticOption::Graphemes),
     );
 
-    let update_reading_time = move |ev| {
-        let storage = window().local_storage().unwrap().unwrap();
-        let value: String = event_target_value(&ev);
-        storage.set_item("reading_time", &value).unwrap();
-        reading_time.set(value.parse::<usize>().unwrap_or_default());
-    };
+    let show_code_points = create_rw_signal(
+        statistics_options
+            .get()
+            .contains(&StatisticOption::CodePoints),
+    );
 
-    let speaking_time = create_rw_signal(
-        storage
-            .get_item("speaking_time")
-            .unwrap()
-            .unwrap_or_else(|| String::from("180"))
-            .parse::<usize>()
-            .unwrap_or_default(),
+    let show_utf8_bytes = create_rw_signal(
+        statistics_options
+            .get()
+            .contains(&StatisticOption::Utf8Bytes),
     );
 
-    let show_speaking_time = create_rw_signal(
+    let show_utf16_units = create_rw_signal(
         statistics_options
             .get()
-            .contains(&StatisticOption::SpeakingTime(speaking_time.get())),
+            .contains(&StatisticOption::Utf16Units),
     );
 
-    let update_speaking_time = move |ev| {
-        let storage = window().local_storage().unwrap().unwrap();
-        let value: String = event_target_value(&ev);
-        storage.clone().set_item("speaking_time", &value).unwrap();
-        speaking_time.set(value.parse::<usize>().unwrap_or_default());
-    };
+    let show_visible_width = create_rw_signal(
+        statistics_options
+            .get()
+            .contains(&StatisticOption::VisibleWidth),
+    );
+
+    let show_letters =
+        create_rw_signal(statistics_options.get().contains(&StatisticOption::Letters));
+
+    let show_digits = create_rw_signal(statistics_options.get().contains(&StatisticOption::Digits));
+
+    let show_punctuation_marks = create_rw_signal(
+        statistics_options
+            .get()
+            .contains(&StatisticOption::PunctuationMarks),
+    );
+
+    let show_uppercase_letters = create_rw_signal(
+        statistics_options
+            .get()
+            .contains(&StatisticOption::UppercaseLetters),
+    );
+
+    let show_lowercase_letters = create_rw_signal(
+        statistics_options
+            .get()
+            .contains(&StatisticOption::LowercaseLetters),
+    );
+
+    let show_emoji = create_rw_signal(statistics_options.get().contains(&StatisticOption::Emoji));
+
+    let show_duplicates = create_rw_signal(
+        statistics_options
+            .get()
+            .contains(&StatisticOption::Duplicates),
+    );
+
+    let show_sms_segments = create_rw_signal(
+        statistics_options
+            .get()
+            .contains(&StatisticOption::SmsSegments),
+    );
+
+    let show_subtitle_characters_per_second = create_rw_signal(
+        statistics_options
+            .get()
+            .contains(&StatisticOption::SubtitleCharactersPerSecond),
+    );
+
+    let show_reading_time = create_rw_signal(
+        statistics_options
+            .get()
+            .contains(&StatisticOption::ReadingTime),
+    );
 
-    let hand_writing_time = create_rw_signal(
-        storage
-            .get_item("hand_writing_time")
-            .unwrap()
-            .unwrap_or_else(|| String::from("68"))
-            .parse::<usize>()
-            .unwrap_or_default(),
+    let show_speaking_time = create_rw_signal(
+        statistics_options
+            .get()
+            .contains(&StatisticOption::SpeakingTime),
     );
 
     let show_hand_writing_time = create_rw_signal(
         statistics_options
             .get()
-            .contains(&StatisticOption::HandWritingTime(hand_writing_time.get())),
+            .contains(&StatisticOption::HandWritingTime),
     );
 
-    let update_hand_writing_time = move |ev| {
+    let markup_options = state.markup_options.get_untracked();
+    let include_headings = create_rw_signal(markup_options.include_headings);
+    let include_captions = create_rw_signal(markup_options.include_captions);
+    let include_footnotes = create_rw_signal(markup_options.include_footnotes);
+
+    let exclusions = state.character_exclusions.get_untracked();
+    let exclude_whitespace = create_rw_signal(exclusions.whitespace);
+    let exclude_punctuation = create_rw_signal(exclusions.punctuation);
+    let exclude_digits = create_rw_signal(exclusions.digits);
+    let exclude_symbols = create_rw_signal(exclusions.symbols);
+
+    create_effect(move |_| {
+        state.character_exclusions.set(CharacterExclusions {
+            whitespace: exclude_whitespace.get(),
+            punctuation: exclude_punctuation.get(),
+            digits: exclude_digits.get(),
+            symbols: exclude_symbols.get(),
+        });
+    });
+
+    let update_source_format = move |ev| {
+        let value: String = event_target_value(&ev);
+        if let Some(format) = SourceFormat::ALL
+            .into_iter()
+            .find(|format| format!("{format:?}") == value)
+        {
+            state.source_format.set(format);
+        }
+    };
+
+    let goal = state.goal.get_untracked();
+    let goal_metric = create_rw_signal(goal.as_ref().map(|goal| goal.metric));
+    let goal_target = create_rw_signal(goal.as_ref().map_or(0, |goal| goal.target));
+    let goal_range = create_rw_signal(goal.as_ref().is_some_and(|goal| goal.maximum.is_some()));
+    let goal_maximum = create_rw_signal(goal.as_ref().and_then(|goal| goal.maximum).unwrap_or(0));
+    let goal_deadline = create_rw_signal(goal.and_then(|goal| goal.deadline).unwrap_or_default());
+
+    let update_goal_metric = move |ev| {
+        let value: String = event_target_value(&ev);
+        goal_metric.set(
+            GoalMetric::ALL
+                .into_iter()
+                .find(|metric| format!("{metric:?}") == value),
+        );
+    };
+
+    create_effect(move |_| {
+        state.goal.set(goal_metric.get().map(|metric| Goal {
+            metric,
+            target: goal_target.get(),
+            maximum: goal_range.get().then(|| goal_maximum.get()),
+            deadline: Some(goal_deadline.get()).filter(|deadline| !deadline.is_empty()),
+        }));
+    });
+
+    let update_character_unit = move |ev| {
+        let value: String = event_target_value(&ev);
+        if let Some(unit) = CharacterUnit::ALL
+            .into_iter()
+            .find(|unit| format!("{unit:?}") == value)
+        {
+            state.character_unit.set(unit);
+        }
+    };
+
+    let update_platform = move |ev| {
         let value: String = event_target_value(&ev);
-        storage.set_item("hand_writing_time", &value).unwrap();
-        hand_writing_time.set(value.parse::<usize>().unwrap_or_default());
+        state.platform.set(
+            Platform::ALL
+                .into_iter()
+                .find(|platform| format!("{platform:?}") == value),
+        );
     };
 
+    create_effect(move |_| {
+        state.markup_options.set(MarkupOptions {
+            include_headings: include_headings.get(),
+            include_captions: include_captions.get(),
+            include_footnotes: include_footnotes.get(),
+        });
+    });
+
     create_effect(move |_| {
         let mut options = Vec::new();
 
@@ -230,15 +444,75 @@ where
         }
 
         if show_reading_time.get() {
-            options.push(StatisticOption::ReadingTime(reading_time.get()));
+            options.push(StatisticOption::ReadingTime);
         }
 
         if show_speaking_time.get() {
-            options.push(StatisticOption::SpeakingTime(speaking_time.get()));
+            options.push(StatisticOption::SpeakingTime);
         }
 
         if show_hand_writing_time.get() {
-            options.push(StatisticOption::HandWritingTime(hand_writing_time.get()));
+            options.push(StatisticOption::HandWritingTime);
+        }
+
+        if show_subtitle_words_per_minute.get() {
+            options.push(StatisticOption::SubtitleWordsPerMinute);
+        }
+
+        if show_subtitle_characters_per_second.get() {
+            options.push(StatisticOption::SubtitleCharactersPerSecond);
+        }
+
+        if show_sms_segments.get() {
+            options.push(StatisticOption::SmsSegments);
+        }
+
+        if show_graphemes.get() {
+            options.push(StatisticOption::Graphemes);
+        }
+
+        if show_code_points.get() {
+            options.push(StatisticOption::CodePoints);
+        }
+
+        if show_utf8_bytes.get() {
+            options.push(StatisticOption::Utf8Bytes);
+        }
+
+        if show_utf16_units.get() {
+            options.push(StatisticOption::Utf16Units);
+        }
+
+        if show_visible_width.get() {
+            options.push(StatisticOption::VisibleWidth);
+        }
+
+        if show_letters.get() {
+            options.push(StatisticOption::Letters);
+        }
+
+        if show_digits.get() {
+            options.push(StatisticOption::Digits);
+        }
+
+        if show_punctuation_marks.get() {
+            options.push(StatisticOption::PunctuationMarks);
+        }
+
+        if show_uppercase_letters.get() {
+            options.push(StatisticOption::UppercaseLetters);
+        }
+
+        if show_lowercase_letters.get() {
+            options.push(StatisticOption::LowercaseLetters);
+        }
+
+        if show_emoji.get() {
+            options.push(StatisticOption::Emoji);
+        }
+
+        if show_duplicates.get() {
+            options.push(StatisticOption::Duplicates);
         }
 
         statistics_options.set(options);
@@ -250,6 +524,82 @@ where
             <h1 class="text-2xl font-semibold">{"Options"}</h1>
 
             <form class="pb-8 mb-4 h-[400px] overflow-auto">
+                <div class="mb-4">
+                    <label class="flex justify-between items-center p-2 text-xl">
+                        {"Source Format"}
+                        <select class="bg-gray-50 border border-gray-300 text-gray-900 text-sm rounded-lg focus:ring-blue-500 focus:border-blue-500 p-2.5 dark:bg-gray-700 dark:border-gray-600 dark:text-white" on:change=update_source_format>
+                            {
+                                SourceFormat::ALL.into_iter().map(|format| view! {
+                                    <option value=format!("{format:?}") selected=move || state.source_format.get() == format>{format.label()}</option>
+                                }).collect::<Vec<_>>()
+                            }
+                        </select>
+                    </label>
+                    <label class="block p-2 text-xl">
+                        {"Section Heading Pattern"}
+                        <input type="text" class="bg-gray-50 border border-gray-300 text-gray-900 text-sm rounded-lg focus:ring-blue-500 focus:border-blue-500 block w-full p-2.5 dark:bg-gray-700 dark:border-gray-600 dark:placeholder-gray-400 dark:text-white dark:focus:ring-blue-500 dark:focus:border-blue-500" prop:value=move || state.heading_pattern.get() placeholder="Chapter \\d+" on:input=move |ev| state.heading_pattern.set(event_target_value(&ev)) />
+                    </label>
+                    <ToggleSwitch label="Script Mode" value=state.script_mode/>
+                    <div class=move || if state.source_format.get() == SourceFormat::Plain { "hidden" } else { "" }>
+                        <ToggleSwitch label="Include Headings" value=include_headings/>
+                        <ToggleSwitch label="Include Captions" value=include_captions/>
+                        <ToggleSwitch label="Include Footnotes" value=include_footnotes/>
+                    </div>
+                </div>
+
+                <div class="mb-4">
+                    <label class="flex justify-between items-center p-2 text-xl">
+                        {"Character Unit"}
+                        <select class="bg-gray-50 border border-gray-300 text-gray-900 text-sm rounded-lg focus:ring-blue-500 focus:border-blue-500 p-2.5 dark:bg-gray-700 dark:border-gray-600 dark:text-white" on:change=update_character_unit>
+                            {
+                                CharacterUnit::ALL.into_iter().map(|unit| view! {
+                                    <option value=format!("{unit:?}") selected=move || state.character_unit.get() == unit>{unit.label()}</option>
+                                }).collect::<Vec<_>>()
+                            }
+                        </select>
+                    </label>
+                    <label class="flex justify-between items-center p-2 text-xl">
+                        {"Character Limit"}
+                        <select class="bg-gray-50 border border-gray-300 text-gray-900 text-sm rounded-lg focus:ring-blue-500 focus:border-blue-500 p-2.5 dark:bg-gray-700 dark:border-gray-600 dark:text-white" on:change=update_platform>
+                            <option value="None" selected=move || state.platform.get().is_none()>{"None"}</option>
+                            {
+                                Platform::ALL.into_iter().map(|platform| view! {
+                                    <option value=format!("{platform:?}") selected=move || state.platform.get() == Some(platform)>{platform.label()}</option>
+                                }).collect::<Vec<_>>()
+                            }
+                        </select>
+                    </label>
+                </div>
+
+                <div class="mb-4">
+                    <label class="flex justify-between items-center p-2 text-xl">
+                        {"Goal"}
+                        <select class="bg-gray-50 border border-gray-300 text-gray-900 text-sm rounded-lg focus:ring-blue-500 focus:border-blue-500 p-2.5 dark:bg-gray-700 dark:border-gray-600 dark:text-white" on:change=update_goal_metric>
+                            <option value="None" selected=move || goal_metric.get().is_none()>{"None"}</option>
+                            {
+                                GoalMetric::ALL.into_iter().map(|metric| view! {
+                                    <option value=format!("{metric:?}") selected=move || goal_metric.get() == Some(metric)>{metric.label()}</option>
+                                }).collect::<Vec<_>>()
+                            }
+                        </select>
+                    </label>
+                    <div class=move || if goal_metric.get().is_none() { "hidden" } else { "" }>
+                        <label class="block p-2 text-xl">
+                            {move || format!("{} ({})", if goal_range.get() { "Minimum" } else { "Target" }, goal_metric.get().unwrap_or_default().unit())}
+                            <input type="number" min="0" class="bg-gray-50 border border-gray-300 text-gray-900 text-sm rounded-lg focus:ring-blue-500 focus:border-blue-500 block w-full p-2.5 dark:bg-gray-700 dark:border-gray-600 dark:placeholder-gray-400 dark:text-white dark:focus:ring-blue-500 dark:focus:border-blue-500" prop:value=move || goal_target.get() placeholder="1500" on:input=move |ev| goal_target.set(event_target_value(&ev).parse::<usize>().unwrap_or_default()) />
+                        </label>
+                        <ToggleSwitch label="Range" value=goal_range/>
+                        <label class=move || if goal_range.get() { "block p-2 text-xl" } else { "hidden" }>
+                            {move || format!("Maximum ({})", goal_metric.get().unwrap_or_default().unit())}
+                            <input type="number" min="0" class="bg-gray-50 border border-gray-300 text-gray-900 text-sm rounded-lg focus:ring-blue-500 focus:border-blue-500 block w-full p-2.5 dark:bg-gray-700 dark:border-gray-600 dark:placeholder-gray-400 dark:text-white dark:focus:ring-blue-500 dark:focus:border-blue-500" prop:value=move || goal_maximum.get() placeholder="2000" on:input=move |ev| goal_maximum.set(event_target_value(&ev).parse::<usize>().unwrap_or_default()) />
+                        </label>
+                        <label class="block p-2 text-xl">
+                            {"Deadline"}
+                            <input type="date" class="bg-gray-50 border border-gray-300 text-gray-900 text-sm rounded-lg focus:ring-blue-500 focus:border-blue-500 block w-full p-2.5 dark:bg-gray-700 dark:border-gray-600 dark:placeholder-gray-400 dark:text-white dark:focus:ring-blue-500 dark:focus:border-blue-500" prop:value=move || goal_deadline.get() on:input=move |ev| goal_deadline.set(event_target_value(&ev)) />
+                        </label>
+                    </div>
+                </div>
+
                 <div class="mb-4">
                     <ToggleSwitch label="Words" value=show_words/>
                 </div>
@@ -272,15 +622,45 @@ where
 
                 <div class="mb-4">
                     <ToggleSwitch label="Character Count (No Spaces)" value=show_character_count_no_spaces/>
+                    <div class=move || if show_character_count_no_spaces.get() { "pl-4" } else { "hidden" }>
+                        <ToggleSwitch label="Exclude Whitespace" value=exclude_whitespace/>
+                        <ToggleSwitch label="Exclude Punctuation" value=exclude_punctuation/>
+                        <ToggleSwitch label="Exclude Digits" value=exclude_digits/>
+                        <ToggleSwitch label="Exclude Symbols" value=exclude_symbols/>
+                    </div>
+                </div>
+
+                <div class="mb-4">
+                    <ToggleSwitch label="Digits" value=show_digits/>
+                </div>
+
+                <div class="mb-4">
+                    <ToggleSwitch label="Duplicates" value=show_duplicates/>
+                </div>
+
+                <div class="mb-4">
+                    <ToggleSwitch label="Emoji" value=show_emoji/>
+                </div>
+
+                <div class="mb-4">
+                    <ToggleSwitch label="Code Points" value=show_code_points/>
+                </div>
+
+                <div class="mb-4">
+                    <ToggleSwitch label="Graphemes" value=show_graphemes/>
                 </div>
 
                 <div class="mb-4">
                     <ToggleSwitch label="Hand Writing Time" value=show_hand_writing_time/>
-                    <div>
-                        <input type="text" class="bg-gray-50 border border-gray-300 text-gray-900 text-sm rounded-lg focus:ring-blue-500 focus:border-blue-500 block w-full p-2.5 dark:bg-gray-700 dark:border-gray-600 dark:placeholder-gray-400 dark:text-white dark:focus:ring-blue-500 dark:focus:border-blue-500" prop:value={move || hand_writing_time.get() } placeholder="68" required on:input=update_hand_writing_time />
+                    <div class=move || if show_hand_writing_time.get() { "pl-4" } else { "hidden" }>
+                        <SpeedProfileEditor activity=Activity::HandWriting/>
                     </div>
                 </div>
 
+                <div class="mb-4">
+                    <ToggleSwitch label="Letters" value=show_letters/>
+                </div>
+
                 <div class="mb-4">
                     <ToggleSwitch label="Line Count" value=show_line_count/>
                 </div>
@@ -289,14 +669,23 @@ where
                     <ToggleSwitch label="Longest Sentence (Words)" value=show_longest_sentence_words/>
                 </div>
 
+                <div class="mb-4">
+                    <ToggleSwitch label="Lowercase Letters" value=show_lowercase_letters/>
+                </div>
+
                 <div class="mb-4">
                     <ToggleSwitch label="Paragraphs" value=show_paragraphs/>
                 </div>
 
+                <div class="mb-4">
+                    <ToggleSwitch label="Punctuation Marks" value=show_punctuation_marks/>
+                </div>
+
                 <div class="mb-4">
                     <ToggleSwitch label="Reading Time" value=show_reading_time/>
-                    <div>
-                        <input type="text" class="bg-gray-50 border border-gray-300 text-gray-900 text-sm rounded-lg focus:ring-blue-500 focus:border-blue-500 block w-full p-2.5 dark:bg-gray-700 dark:border-gray-600 dark:placeholder-gray-400 dark:text-white dark:focus:ring-blue-500 dark:focus:border-blue-500" prop:value={move || reading_time.get() } placeholder="275" required on:input=update_reading_time />
+                    <div class=move || if show_reading_time.get() { "pl-4" } else { "hidden" }>
+                        <SpeedProfileEditor activity=Activity::Reading/>
+                        <ToggleSwitch label="Adjust for Complexity" value=state.adjust_reading_time/>
                     </div>
                 </div>
 
@@ -308,16 +697,44 @@ where
                     <ToggleSwitch label="Shortest Sentence (Words)" value=show_shortest_sentence_words/>
                 </div>
 
+                <div class="mb-4">
+                    <ToggleSwitch label="SMS Segments" value=show_sms_segments/>
+                </div>
+
                 <div class="mb-4">
                     <ToggleSwitch label="Speaking Time" value=show_speaking_time/>
-                    <div>
-                        <input type="text" class="bg-gray-50 border border-gray-300 text-gray-900 text-sm rounded-lg focus:ring-blue-500 focus:border-blue-500 block w-full p-2.5 dark:bg-gray-700 dark:border-gray-600 dark:placeholder-gray-400 dark:text-white dark:focus:ring-blue-500 dark:focus:border-blue-500" prop:value={move || speaking_time.get() } placeholder="180" required on:input=update_speaking_time />
+                    <div class=move || if show_speaking_time.get() { "pl-4" } else { "hidden" }>
+                        <SpeedProfileEditor activity=Activity::Speaking/>
                     </div>
                 </div>
 
+                <div class="mb-4">
+                    <ToggleSwitch label="Subtitle Characters per Second" value=show_subtitle_characters_per_second/>
+                </div>
+
+                <div class="mb-4">
+                    <ToggleSwitch label="Subtitle Words per Minute" value=show_subtitle_words_per_minute/>
+                </div>
+
                 <div class="mb-4">
                     <ToggleSwitch label="Unique Words" value=show_unique_words/>
                 </div>
+
+                <div class="mb-4">
+                    <ToggleSwitch label="Uppercase Letters" value=show_uppercase_letters/>
+                </div>
+
+                <div class="mb-4">
+                    <ToggleSwitch label="UTF-8 Bytes" value=show_utf8_bytes/>
+                </div>
+
+                <div class="mb-4">
+                    <ToggleSwitch label="UTF-16 Units" value=show_utf16_units/>
+                </div>
+
+                <div class="mb-4">
+                    <ToggleSwitch label="Visible Width" value=show_visible_width/>
+                </div>
             </form>
 
             <div class="flex justify-end">
@@ -328,51 +745,483 @@ where
     }
 }
 
+/// Strips `text` of markup whenever it or the format changes, so that the
+/// statistics all read the same stripped text.
+fn strip_markup(
+    text: RwSignal<String>,
+    format: RwSignal<SourceFormat>,
+    options: RwSignal<MarkupOptions>,
+) -> Signal<String> {
+    create_memo(move |_| text.with(|text| markup::prose(text, format.get(), options.get()))).into()
+}
+
+/// Finds the duplicates in `text` whenever it changes, as long as the panel
+/// is open, the editor highlights them or their count is a statistic shown.
+/// Otherwise the search, which is slow on long texts, is skipped.
+fn find_duplicates(
+    text: RwSignal<String>,
+    options: RwSignal<DuplicateOptions>,
+    statistics_options: RwSignal<Vec<StatisticOption>>,
+    open: RwSignal<bool>,
+) -> Signal<Vec<DuplicateGroup>> {
+    create_memo(move |_| {
+        let options = options.get();
+        let shown = open.get()
+            || options.highlight
+            || statistics_options
+                .with(|statistics| statistics.contains(&StatisticOption::Duplicates));
+        if !shown {
+            return Vec::new();
+        }
+
+        text.with(|text| duplicates::find(text, options.similarity))
+    })
+    .into()
+}
+
+/// Blanks out the markup in `text` whenever it or the format changes, for
+/// the analyses that point back into the editor.
+fn mask_markup(text: RwSignal<String>, format: RwSignal<SourceFormat>) -> Signal<String> {
+    create_memo(move |_| text.with(|text| markup::mask(text, format.get()))).into()
+}
+
+/// Finds the repetitions in the `masked` text whenever it or the settings
+/// change, so that tag and command names are not reported.
+fn find_repetitions(
+    masked: Signal<String>,
+    options: RwSignal<RepetitionOptions>,
+) -> Signal<Vec<Repetition>> {
+    create_memo(move |_| {
+        let window = options.with(|options| options.window);
+        masked.with(|text| repetition::find(text, window))
+    })
+    .into()
+}
+
 #[derive(Debug, Default, Clone, Copy)]
 pub struct GlobalState {
     pub text: RwSignal<String>,
     pub match_case: RwSignal<bool>,
     pub dictionary: RwSignal<HashMap<String, u32>>,
     pub statistics_options: RwSignal<Vec<StatisticOption>>,
+    pub subtitles: RwSignal<Vec<Cue>>,
+    pub subtitle_limits: RwSignal<ReadingSpeedLimits>,
+    pub source_format: RwSignal<SourceFormat>,
+    pub markup_options: RwSignal<MarkupOptions>,
+    pub heading_pattern: RwSignal<String>,
+    pub goal: RwSignal<Option<Goal>>,
+    pub platform: RwSignal<Option<Platform>>,
+    pub character_unit: RwSignal<CharacterUnit>,
+    pub character_exclusions: RwSignal<CharacterExclusions>,
+    pub speed_profiles: RwSignal<SpeedProfiles>,
+    pub adjust_reading_time: RwSignal<bool>,
+    pub script_mode: RwSignal<bool>,
+    pub translation: RwSignal<Translation>,
+    pub max_expansion: RwSignal<f64>,
+    pub discount_grid: RwSignal<DiscountGrid>,
+    pub repetition_options: RwSignal<RepetitionOptions>,
+    pub duplicate_options: RwSignal<DuplicateOptions>,
+    pub documents: RwSignal<Vec<Document>>,
+    pub current_document: RwSignal<u64>,
+    pub storage_error: RwSignal<Option<String>>,
+    pub revisions: RwSignal<Vec<Revision>>,
+    pub undo_stack: RwSignal<Vec<UndoEntry>>,
+    pub writing_log: RwSignal<WritingLog>,
+    /// The creation time of the undo entry offered in the toast, if any.
+    pub undo_toast: RwSignal<Option<f64>>,
+    /// Counts the times an action such as Clear or undo replaced the text,
+    /// so that the writing log can tell those changes from writing.
+    pub text_replacements: RwSignal<u64>,
+    /// The text compared against the open document. It is not saved.
+    pub compare_text: RwSignal<String>,
+    /// Files imported for a word count analysis. They are not saved.
+    pub quote_files: RwSignal<Vec<QuoteFile>>,
+    /// Whether the duplicates panel shows its findings. It is not saved.
+    pub duplicates_open: RwSignal<bool>,
+    /// The duplicates in the text, found once per change and only while
+    /// something shows them.
+    pub duplicate_groups: Signal<Vec<DuplicateGroup>>,
+    /// The repetitions in the text, found once per change.
+    pub repetition_matches: Signal<Vec<Repetition>>,
+    /// The text with its markup stripped, as the statistics count it.
+    pub prose_text: Signal<String>,
+    /// The text with its markup blanked out, so that offsets into it are
+    /// offsets into the editor text.
+    pub masked_text: Signal<String>,
+    /// The heading pattern, compiled when it changes.
+    pub heading_regex: Signal<Option<HeadingPattern>>,
 }
 
 impl GlobalState {
-    fn new() -> Self {
-        let storage = window().local_storage().unwrap().unwrap();
-
-        let text = create_rw_signal(storage.get_item("text").unwrap().unwrap_or_default());
-        let case_insensitive = create_rw_signal(
-            storage
-                .get_item("case_insensitive")
-                .unwrap()
-                .map_or_else(|| false, |s| s.parse::<bool>().unwrap_or(true)),
-        );
-        let dictionary = create_rw_signal(HashMap::new());
-        let statistics_options =
-            create_rw_signal(storage.get_item("statistics_options").unwrap().map_or_else(
-                || {
-                    vec![
-                        StatisticOption::Words,
-                        StatisticOption::Characters,
-                        StatisticOption::Sentences,
-                        StatisticOption::Paragraphs,
-                        StatisticOption::ReadingTime(275),
-                        StatisticOption::SpeakingTime(180),
-                    ]
-                },
-                |s| serde_json::from_str(&s).unwrap(),
-            ));
+    fn new(storage: &Storage) -> Self {
+        let (library, current, unreadable) = documents::load(storage);
+        let document = library
+            .iter()
+            .find(|document| document.id == current)
+            .cloned()
+            .unwrap();
+        let settings = document.settings;
+
+        let text = create_rw_signal(document.text);
+        let statistics_options = create_rw_signal(settings.statistics_options);
+        let duplicate_options = create_rw_signal(settings.duplicate_options);
+        let duplicates_open = create_rw_signal(false);
+        let source_format = create_rw_signal(settings.source_format);
+        let markup_options = create_rw_signal(settings.markup_options);
+        let heading_pattern = create_rw_signal(settings.heading_pattern);
+        let repetition_options = create_rw_signal(settings.repetition_options);
+        let masked_text = mask_markup(text, source_format);
 
         Self {
             text,
-            match_case: case_insensitive,
-            dictionary,
+            match_case: create_rw_signal(settings.match_case),
+            dictionary: create_rw_signal(HashMap::new()),
             statistics_options,
+            subtitles: create_rw_signal(document.subtitles),
+            subtitle_limits: create_rw_signal(settings.subtitle_limits),
+            source_format,
+            markup_options,
+            heading_pattern,
+            goal: create_rw_signal(settings.goal),
+            platform: create_rw_signal(settings.platform),
+            character_unit: create_rw_signal(settings.character_unit),
+            character_exclusions: create_rw_signal(settings.character_exclusions),
+            speed_profiles: create_rw_signal(settings.speed_profiles),
+            adjust_reading_time: create_rw_signal(settings.adjust_reading_time),
+            script_mode: create_rw_signal(settings.script_mode),
+            translation: create_rw_signal(document.translation),
+            max_expansion: create_rw_signal(settings.max_expansion),
+            discount_grid: create_rw_signal(settings.discount_grid),
+            repetition_options,
+            duplicate_options,
+            documents: create_rw_signal(library),
+            current_document: create_rw_signal(current),
+            storage_error: create_rw_signal((unreadable > 0).then(|| {
+                format!(
+                    "{unreadable} saved document(s) could not be read. They were set aside \
+                     unchanged and left out of the library."
+                )
+            })),
+            revisions: create_rw_signal(history::load(storage, current)),
+            undo_stack: create_rw_signal(undo::load(storage)),
+            writing_log: create_rw_signal(sessions::load(storage)),
+            undo_toast: create_rw_signal(None),
+            text_replacements: create_rw_signal(0),
+            compare_text: create_rw_signal(String::new()),
+            quote_files: create_rw_signal(Vec::new()),
+            duplicates_open,
+            duplicate_groups: find_duplicates(
+                text,
+                duplicate_options,
+                statistics_options,
+                duplicates_open,
+            ),
+            repetition_matches: find_repetitions(masked_text, repetition_options),
+            prose_text: strip_markup(text, source_format, markup_options),
+            masked_text,
+            heading_regex: create_memo(move |_| {
+                heading_pattern.with(|pattern| HeadingPattern::new(pattern))
+            })
+            .into(),
         }
     }
 
-    pub fn word_count(&self) -> usize {
+    /// The same statistics with the same settings, computed from `text`.
+    pub fn with_text(&self, text: RwSignal<String>) -> Self {
+        let masked_text = mask_markup(text, self.source_format);
+
+        Self {
+            text,
+            duplicate_groups: find_duplicates(
+                text,
+                self.duplicate_options,
+                self.statistics_options,
+                self.duplicates_open,
+            ),
+            repetition_matches: find_repetitions(masked_text, self.repetition_options),
+            prose_text: strip_markup(text, self.source_format, self.markup_options),
+            masked_text,
+            ..*self
+        }
+    }
+
+    /// The value of `option` as a number. Times are in seconds.
+    pub fn statistic(&self, option: StatisticOption) -> f64 {
+        let value = match option {
+            StatisticOption::Characters => self.character_total() as f64,
+            StatisticOption::CharacterCountNoSpaces => self.character_count_no_spaces() as f64,
+            StatisticOption::LineCount => self.line_count() as f64,
+            StatisticOption::Paragraphs => self.paragraph_count() as f64,
+            StatisticOption::Sentences => self.sentence_count() as f64,
+            StatisticOption::LongestSentenceWords => {
+                self.show_longest_sentence_words_count() as f64
+            }
+            StatisticOption::ShortestSentenceWords => {
+                self.show_shortest_sentence_words_count() as f64
+            }
+            StatisticOption::AvgSentenceWords => self.avg_sentence_words(),
+            StatisticOption::AvgSentenceChars => self.avg_sentence_chars(),
+            StatisticOption::AvgWordLength => self.avg_word_count(),
+            StatisticOption::ReadingTime => self.activity_time(Activity::Reading).as_secs_f64(),
+            StatisticOption::SpeakingTime => self.activity_time(Activity::Speaking).as_secs_f64(),
+            StatisticOption::HandWritingTime => {
+                self.activity_time(Activity::HandWriting).as_secs_f64()
+            }
+            StatisticOption::UniqueWords => self.unique_word_count() as f64,
+            StatisticOption::Words => self.word_count() as f64,
+            StatisticOption::SubtitleWordsPerMinute => self.subtitle_words_per_minute(),
+            StatisticOption::SubtitleCharactersPerSecond => self.subtitle_characters_per_second(),
+            StatisticOption::SmsSegments => self.sms_analysis().segments as f64,
+            StatisticOption::Graphemes => self.character_count_in(CharacterUnit::Graphemes) as f64,
+            StatisticOption::CodePoints => {
+                self.character_count_in(CharacterUnit::CodePoints) as f64
+            }
+            StatisticOption::Utf8Bytes => self.character_count_in(CharacterUnit::Utf8Bytes) as f64,
+            StatisticOption::Utf16Units => {
+                self.character_count_in(CharacterUnit::Utf16Units) as f64
+            }
+            StatisticOption::VisibleWidth => {
+                self.character_count_in(CharacterUnit::VisibleWidth) as f64
+            }
+            StatisticOption::Letters => self.class_count(CharacterClass::Letters) as f64,
+            StatisticOption::Digits => self.class_count(CharacterClass::Digits) as f64,
+            StatisticOption::PunctuationMarks => {
+                self.class_count(CharacterClass::Punctuation) as f64
+            }
+            StatisticOption::UppercaseLetters => self.class_count(CharacterClass::Uppercase) as f64,
+            StatisticOption::LowercaseLetters => self.class_count(CharacterClass::Lowercase) as f64,
+            StatisticOption::Emoji => self.class_count(CharacterClass::Emoji) as f64,
+            StatisticOption::Duplicates => self.duplicate_count() as f64,
+        };
+
+        // Averages over a text without sentences or words are undefined.
+        if value.is_finite() {
+            value
+        } else {
+            0.0
+        }
+    }
+
+    /// The settings of the open document as currently shown in the editor.
+    pub fn settings(&self) -> DocumentSettings {
+        DocumentSettings {
+            match_case: self.match_case.get(),
+            statistics_options: self.statistics_options.get(),
+            source_format: self.source_format.get(),
+            markup_options: self.markup_options.get(),
+            heading_pattern: self.heading_pattern.get(),
+            subtitle_limits: self.subtitle_limits.get(),
+            goal: self.goal.get(),
+            platform: self.platform.get(),
+            character_unit: self.character_unit.get(),
+            character_exclusions: self.character_exclusions.get(),
+            speed_profiles: self.speed_profiles.get(),
+            adjust_reading_time: self.adjust_reading_time.get(),
+            script_mode: self.script_mode.get(),
+            max_expansion: self.max_expansion.get(),
+            discount_grid: self.discount_grid.get(),
+            repetition_options: self.repetition_options.get(),
+            duplicate_options: self.duplicate_options.get(),
+        }
+    }
+
+    /// Copies the editor state into the open document, touching its
+    /// modification time only when the text changed.
+    pub fn sync_document(&self) {
         let text = self.text.get();
+        let subtitles = self.subtitles.get();
+        let translation = self.translation.get();
+        let settings = self.settings();
+        let current = self.current_document.get_untracked();
+
+        self.documents.update(|documents| {
+            if let Some(document) = documents.iter_mut().find(|document| document.id == current) {
+                if document.text != text {
+                    document.text = text;
+                    document.modified = Date::now();
+                }
+
+                document.subtitles = subtitles;
+                document.translation = translation;
+                document.settings = settings;
+            }
+        });
+    }
+
+    pub fn open_document(&self, id: u64) {
+        let Some(document) = self
+            .documents
+            .get_untracked()
+            .into_iter()
+            .find(|document| document.id == id)
+        else {
+            return;
+        };
+
+        batch(|| {
+            let settings = document.settings;
+            self.text.set(document.text);
+            self.subtitles.set(document.subtitles);
+            self.translation.set(document.translation);
+            self.match_case.set(settings.match_case);
+            self.statistics_options.set(settings.statistics_options);
+            self.source_format.set(settings.source_format);
+            self.markup_options.set(settings.markup_options);
+            self.heading_pattern.set(settings.heading_pattern);
+            self.subtitle_limits.set(settings.subtitle_limits);
+            self.goal.set(settings.goal);
+            self.platform.set(settings.platform);
+            self.character_unit.set(settings.character_unit);
+            self.character_exclusions.set(settings.character_exclusions);
+            self.speed_profiles.set(settings.speed_profiles);
+            self.adjust_reading_time.set(settings.adjust_reading_time);
+            self.script_mode.set(settings.script_mode);
+            self.max_expansion.set(settings.max_expansion);
+            self.discount_grid.set(settings.discount_grid);
+            self.repetition_options.set(settings.repetition_options);
+            self.duplicate_options.set(settings.duplicate_options);
+            self.current_document.set(id);
+        });
+    }
+
+    pub fn save_version(&self, name: Option<String>) {
+        let text = self.text.get_untracked();
+        self.revisions
+            .update(|revisions| _ = history::record(revisions, text, name));
+    }
+
+    /// Takes an automatic snapshot if the text changed since the latest one
+    /// and the snapshot interval has passed.
+    pub fn autosave(&self) {
+        let text = self.text.get_untracked();
+        if history::is_due(&self.revisions.get_untracked(), &text) {
+            self.revisions
+                .update(|revisions| _ = history::record(revisions, text, None));
+        }
+    }
+
+    /// Replaces the text with `revision`, first saving the current text as a
+    /// version so that restoring never loses work.
+    pub fn restore_revision(&self, revision: &Revision) {
+        let text = self.text.get_untracked();
+        let name = format!("Before restoring {}", revision.label());
+        self.revisions
+            .update(|revisions| history::record_before_restore(revisions, text, name));
+        self.replace_text(
+            format!("Restored {}", revision.label()),
+            revision.text.clone(),
+            self.subtitles.get_untracked(),
+        );
+    }
+
+    /// Replaces the text of the open document, keeping what it replaces on
+    /// the undo stack and offering to undo it.
+    pub fn replace_text(&self, label: String, text: String, subtitles: Vec<Cue>) {
+        let entry = UndoEntry::new(
+            label,
+            self.current_document.get_untracked(),
+            self.text.get_untracked(),
+            self.subtitles.get_untracked(),
+        );
+
+        batch(|| {
+            self.undo_toast.set(Some(entry.created));
+            self.undo_stack.update(|stack| undo::push(stack, entry));
+            self.text_replacements.update(|count| *count += 1);
+            self.text.set(text);
+            self.subtitles.set(subtitles);
+        });
+    }
+
+    /// Puts back the text replaced by the most recent action, switching to
+    /// its document if another one is open.
+    pub fn undo(&self) {
+        let mut stack = self.undo_stack.get_untracked();
+        let Some(entry) = stack.pop() else {
+            return;
+        };
+
+        if entry.document != self.current_document.get_untracked() {
+            self.open_document(entry.document);
+        }
+
+        batch(|| {
+            self.undo_stack.set(stack);
+            self.undo_toast.set(None);
+            self.text_replacements.update(|count| *count += 1);
+            self.text.set(entry.text);
+            self.subtitles.set(entry.subtitles);
+        });
+    }
+
+    pub fn create_document(&self) {
+        let mut documents = self.documents.get_untracked();
+        let id = documents::next_id(&documents);
+        documents.push(Document::new(id, format!("Untitled {id}")));
+        self.documents.set(documents);
+        self.open_document(id);
+    }
+
+    pub fn duplicate_document(&self, id: u64) {
+        let mut documents = self.documents.get_untracked();
+        let Some(original) = documents.iter().find(|document| document.id == id) else {
+            return;
+        };
+
+        let copy = Document {
+            id: documents::next_id(&documents),
+            name: format!("{} (Copy)", original.name),
+            modified: Date::now(),
+            ..original.clone()
+        };
+        let copy_id = copy.id;
+        documents.push(copy);
+        self.documents.set(documents);
+        self.open_document(copy_id);
+    }
+
+    pub fn rename_document(&self, id: u64, name: String) {
+        self.documents.update(|documents| {
+            if let Some(document) = documents.iter_mut().find(|document| document.id == id) {
+                document.name = name;
+            }
+        });
+    }
+
+    pub fn delete_document(&self, id: u64) {
+        let mut documents = self.documents.get_untracked();
+        documents.retain(|document| document.id != id);
+
+        if documents.is_empty() {
+            documents.push(Document::new(
+                documents::next_id(&[]),
+                String::from("Untitled"),
+            ));
+        }
+
+        let next = documents[0].id;
+        self.documents.set(documents);
+        self.undo_stack
+            .update(|stack| stack.retain(|entry| entry.document != id));
+
+        if self.current_document.get_untracked() == id {
+            self.open_document(next);
+        }
+    }
+
+    /// The text that statistics are computed from, with the markup of the
+    /// selected source format stripped.
+    pub fn content(&self) -> String {
+        self.prose_text.get()
+    }
+
+    /// Strips `text` of markup the same way as the open document.
+    pub fn prose(&self, text: &str) -> String {
+        markup::prose(text, self.source_format.get(), self.markup_options.get())
+    }
+
+    pub fn word_count(&self) -> usize {
+        let text = self.content();
         let pattern = &WORD_REGEX;
         let matches = pattern.find_iter(&text);
 
@@ -380,16 +1229,17 @@ impl GlobalState {
     }
 
     pub fn avg_word_count(&self) -> f64 {
-        let text = self.text.get();
+        let text = self.content();
         let pattern = &WORD_REGEX;
         let matches = pattern.find_iter(&text);
-        let words: Vec<usize> = matches.map(|m| m.as_str().len()).collect();
+        let unit = self.character_unit.get();
+        let words: Vec<usize> = matches.map(|m| unit.count(m.as_str())).collect();
 
         words.iter().sum::<usize>() as f64 / words.len() as f64
     }
 
     pub fn avg_sentence_words(&self) -> f64 {
-        let text = self.text.get();
+        let text = self.content();
 
         let sentences: Vec<usize> = {
             let pattern = &SENTENCE_REGEX;
@@ -407,7 +1257,7 @@ impl GlobalState {
     }
 
     pub fn avg_sentence_chars(&self) -> f64 {
-        let text = self.text.get();
+        let text = self.content();
 
         let sentences: Vec<usize> = {
             let pattern = &SENTENCE_REGEX;
@@ -415,11 +1265,11 @@ impl GlobalState {
             matches.map(|m| m.as_str().len()).collect()
         };
 
-        text.chars().count() as f64 / sentences.len() as f64
+        self.character_unit.get().count(&text) as f64 / sentences.len() as f64
     }
 
     pub fn unique_word_count(&self) -> usize {
-        let text = self.text.get();
+        let text = self.content();
         let pattern = &WORD_REGEX;
         let matches = pattern.find_iter(&text);
         let words: Vec<&str> = matches.map(|m| m.as_str()).collect();
@@ -438,7 +1288,7 @@ impl GlobalState {
     }
 
     pub fn sentence_count(&self) -> usize {
-        let text = self.text.get();
+        let text = self.content();
         let pattern = &SENTENCE_REGEX;
         let matches = pattern.find_iter(&text);
 
@@ -446,7 +1296,7 @@ impl GlobalState {
     }
 
     pub fn show_longest_sentence_words_count(&self) -> usize {
-        let text = self.text.get();
+        let text = self.content();
         let patten = &SENTENCE_REGEX;
         let matches = patten.find_iter(&text);
         let sentences: Vec<&str> = matches.map(|m| m.as_str()).collect();
@@ -466,7 +1316,7 @@ impl GlobalState {
     }
 
     pub fn show_shortest_sentence_words_count(&self) -> usize {
-        let text = self.text.get();
+        let text = self.content();
         let patten = &SENTENCE_REGEX;
         let matches = patten.find_iter(&text);
         let sentences: Vec<&str> = matches.map(|m| m.as_str()).collect();
@@ -486,7 +1336,7 @@ impl GlobalState {
     }
 
     pub fn paragraph_count(&self) -> usize {
-        let text = self.text.get();
+        let text = self.content();
         if text.is_empty() {
             return 0;
         }
@@ -496,31 +1346,228 @@ impl GlobalState {
         pattern.split(&text).count()
     }
 
+    /// Characters outside the classes excluded in the options.
     pub fn character_count_no_spaces(&self) -> usize {
-        self.text.get().chars().filter(|char| *char != ' ').count()
+        self.character_exclusions
+            .get()
+            .count(&self.content(), self.character_unit.get())
+    }
+
+    pub fn class_count(&self, class: CharacterClass) -> usize {
+        class.count(&self.content(), self.character_unit.get())
     }
 
+    /// Characters in the unit chosen for the document.
     pub fn character_total(&self) -> usize {
-        self.text.get().chars().count()
+        self.character_count_in(self.character_unit.get())
+    }
+
+    pub fn character_count_in(&self, unit: CharacterUnit) -> usize {
+        unit.count(&self.content())
     }
 
     pub fn line_count(&self) -> usize {
         self.text.get().lines().count()
     }
+
+    pub fn sections(&self) -> Vec<Section> {
+        let markdown = self.source_format.get() == SourceFormat::Markdown;
+
+        self.heading_regex.with(|pattern| {
+            self.text
+                .with(|text| outline::sections(text, markdown, pattern.as_ref()))
+        })
+    }
+
+    pub fn section_word_count(&self, section: &Section) -> usize {
+        let text = self.text.get();
+        let content = markup::prose(
+            &text[section.start..section.end],
+            self.source_format.get(),
+            self.markup_options.get(),
+        );
+
+        WORD_REGEX.find_iter(&content).count()
+    }
+
+    /// The words per minute of the document's reading profile.
+    pub fn reading_words_per_minute(&self) -> usize {
+        self.speed_profiles.get().reading.rate as usize
+    }
+
+    /// How long `activity` takes for the whole text with the document's
+    /// profile for it, including pauses. In script mode, speaking follows
+    /// the script's cues.
+    pub fn activity_time(&self, activity: Activity) -> Duration {
+        if activity == Activity::Speaking && self.script_mode.get() {
+            return self
+                .script_timings()
+                .iter()
+                .map(|timing| timing.duration)
+                .sum();
+        }
+
+        let units = match activity {
+            Activity::Reading | Activity::Speaking => self.word_count(),
+            Activity::HandWriting => self.character_total(),
+        };
+
+        self.speed_profiles.get().get(activity).duration(
+            units,
+            self.sentence_count(),
+            self.paragraph_count(),
+        )
+    }
+
+    /// The spoken words, length and start of each slide of the script.
+    pub fn script_timings(&self) -> Vec<SlideTiming> {
+        let profile = self.speed_profiles.get().speaking;
+        let mut starts_at = Duration::ZERO;
+
+        script::slides(&self.text.get())
+            .into_iter()
+            .map(|slide| {
+                let spoken = self.prose(&slide.spoken);
+                let words = WORD_REGEX.find_iter(&spoken).count();
+                let sentences = SENTENCE_REGEX.find_iter(&spoken).count();
+                let paragraphs = PARAGRAPH_REGEX.split(spoken.trim()).count();
+                let duration = profile.duration(words, sentences, paragraphs)
+                    + Duration::from_secs_f64(slide.pause);
+
+                let timing = SlideTiming {
+                    slide,
+                    words,
+                    duration,
+                    starts_at,
+                };
+                starts_at += duration;
+                timing
+            })
+            .collect()
+    }
+
+    /// The reading time adjusted for difficulty, images, code, numbers and
+    /// tables.
+    pub fn adjusted_reading_time(&self) -> ReadingEstimate {
+        ReadingEstimate::of(
+            &self.text.get(),
+            &self.content(),
+            self.source_format.get(),
+            self.markup_options.get(),
+            self.activity_time(Activity::Reading),
+        )
+    }
+
+    /// The current value of what `metric` measures, in the goal's unit.
+    pub fn goal_value(&self, metric: GoalMetric) -> f64 {
+        match metric {
+            GoalMetric::Words => self.word_count() as f64,
+            GoalMetric::Characters => self.character_total() as f64,
+            GoalMetric::ReadingTime => self.activity_time(Activity::Reading).as_secs_f64() / 60.0,
+        }
+    }
+
+    /// Repeated words, phrases and sentence openings, located in the text as
+    /// typed so that they can be shown in the editor.
+    pub fn repetitions(&self) -> Vec<Repetition> {
+        self.repetition_matches.get()
+    }
+
+    /// The length, sentences and dialogue of each paragraph, located in the
+    /// text as typed. Markup is left out as in the statistics, so code blocks
+    /// and tags do not make paragraphs of their own.
+    pub fn paragraph_metrics(&self) -> Vec<ParagraphMetrics> {
+        self.masked_text.with(|text| paragraphs::analyze(text))
+    }
+
+    /// The words, characters, syllables and grade of each sentence, located
+    /// in the text as typed. Markup is left out as in the statistics, but
+    /// headings, captions and footnotes are measured even when the markup
+    /// options leave them out, since they stay in the editor.
+    pub fn sentence_metrics(&self) -> Vec<SentenceMetrics> {
+        let unit = self.character_unit.get();
+        self.masked_text.with(|text| sentences::analyze(text, unit))
+    }
+
+    /// Sentences and paragraphs that repeat, exactly or nearly.
+    pub fn duplicates(&self) -> Vec<DuplicateGroup> {
+        self.duplicate_groups.get()
+    }
+
+    /// How many sentences and paragraphs are copies of an earlier one.
+    pub fn duplicate_count(&self) -> usize {
+        self.duplicate_groups
+            .with(|groups| groups.iter().map(DuplicateGroup::copies).sum())
+    }
+
+    /// The ranges of the text the editor highlights.
+    pub fn highlights(&self) -> Vec<Range<usize>> {
+        let mut highlights = Vec::new();
+
+        if self.repetition_options.get().highlight {
+            highlights.extend(
+                self.repetitions()
+                    .into_iter()
+                    .flat_map(|repetition| [repetition.first, repetition.second]),
+            );
+        }
+
+        if self.duplicate_options.get().highlight {
+            highlights.extend(
+                self.duplicates()
+                    .into_iter()
+                    .flat_map(|group| group.instances),
+            );
+        }
+
+        highlights
+    }
+
+    pub fn sms_analysis(&self) -> SmsAnalysis {
+        sms::analyze(&self.content())
+    }
+
+    pub fn subtitle_words_per_minute(&self) -> f64 {
+        subtitles::total_words_per_minute(&self.subtitles.get())
+    }
+
+    pub fn subtitle_characters_per_second(&self) -> f64 {
+        subtitles::total_characters_per_second(&self.subtitles.get())
+    }
 }
 
 #[component]
-pub fn App() -> impl IntoView {
-    let storage = window().local_storage().unwrap().unwrap();
-    let state = GlobalState::new();
+pub fn App(storage: Storage) -> impl IntoView {
+    let state = GlobalState::new(&storage);
     provide_context(state);
+    provide_context(storage.clone());
+
+    storage.on_error(move |error| {
+        state
+            .storage_error
+            .set(Some(format!("Your changes could not be saved: {error}.")));
+    });
+
+    let library_open = create_rw_signal(false);
+    let history_open = create_rw_signal(false);
+    let teleprompter_open = create_rw_signal(false);
+    let compare_open = create_rw_signal(false);
+    let translation_open = create_rw_signal(false);
 
     let update_text = move |ev| {
         let value: String = event_target_value(&ev);
-        storage.set_item("text", &value).unwrap();
         state.text.set(value);
     };
 
+    let highlight_layer = create_node_ref::<Div>();
+
+    let sync_highlights = move |ev: Event| {
+        let editor = event_target::<HtmlTextAreaElement>(&ev);
+        if let Some(layer) = highlight_layer.get() {
+            layer.set_scroll_top(editor.scroll_top());
+        }
+    };
+
     let get_result = move || {
         spawn_local(async move {
             if state.text.get().is_empty() {
@@ -546,31 +1593,102 @@ pub fn App() -> impl IntoView {
                 occurrence
             };
 
-            state.dictionary.set(word_occurrences(state.text.get()));
+            state.dictionary.set(word_occurrences(state.content()));
         });
     };
 
     let clear_input = move |ev: MouseEvent| {
         ev.prevent_default();
-        let storage = window().local_storage().unwrap().unwrap();
         spawn_local(async move {
             if state.text.get().is_empty() {
                 return;
             }
 
             state.dictionary.set(HashMap::new());
-            state.text.set(String::new());
-            storage.set_item("text", "").unwrap();
+            state.replace_text(String::from("Cleared input"), String::new(), Vec::new());
         });
     };
 
     let toggle_case_insensitive = move |_| {
-        let storage = window().local_storage().unwrap().unwrap();
-
         state.match_case.set(!state.match_case.get());
-        storage
-            .set("match_case", &state.match_case.get().to_string())
-            .unwrap();
+    };
+
+    let subtitle_input = create_node_ref::<Input>();
+
+    let open_subtitles = move |_: MouseEvent| {
+        if let Some(input) = subtitle_input.get() {
+            input.click();
+        }
+    };
+
+    let import_subtitles = move |_| {
+        let Some(file) = subtitle_input
+            .get()
+            .and_then(|input| input.files())
+            .and_then(|files| files.get(0))
+        else {
+            return;
+        };
+
+        spawn_local(async move {
+            let Ok(source) = read_as_text(&File::from(file)).await else {
+                return;
+            };
+
+            let cues = subtitles::parse(&source);
+            if cues.is_empty() {
+                return;
+            }
+
+            state.replace_text(
+                String::from("Imported subtitles"),
+                subtitles::transcript(&cues),
+                cues,
+            );
+        });
+    };
+
+    let quote_input = create_node_ref::<Input>();
+
+    let open_quote_files = move |_: MouseEvent| {
+        if let Some(input) = quote_input.get() {
+            input.click();
+        }
+    };
+
+    let import_quote_files = move |_| {
+        let Some(files) = quote_input.get().and_then(|input| input.files()) else {
+            return;
+        };
+        let files: Vec<web_sys::File> = (0..files.length())
+            .filter_map(|index| files.get(index))
+            .collect();
+
+        spawn_local(async move {
+            for file in files {
+                let name = file.name();
+                let Ok(contents) = read_as_text(&File::from(file)).await else {
+                    continue;
+                };
+
+                match Translation::import(&name, &contents) {
+                    Ok(translation) => state.quote_files.update(|files| {
+                        files.push(QuoteFile {
+                            name,
+                            segments: translation.segments,
+                        })
+                    }),
+                    Err(error) => {
+                        _ = window()
+                            .alert_with_message(&format!("Could not import {name}: {error}."));
+                    }
+                }
+            }
+        });
+
+        if let Some(input) = quote_input.get() {
+            input.set_value("");
+        }
     };
 
     let open_options = |_: MouseEvent| {
@@ -590,40 +1708,112 @@ pub fn App() -> impl IntoView {
     };
 
     let export_csv = move |_: MouseEvent| {
-        struct Record {
-            word: String,
-            count: usize,
-        }
+        download_csv("results.csv", &["Word", "Count"], state.dictionary.get());
+    };
 
-        let result = Vec::new();
-        let mut wtr = csv::Writer::from_writer(result);
+    create_effect(move |_| {
+        let _ = state.text.get();
+        let _ = state.match_case.get();
+        let _ = state.source_format.get();
+        let _ = state.markup_options.get();
+        get_result();
+    });
 
-        // We still need to write headers manually.
-        wtr.write_record(["Word", "Count"]).unwrap();
+    create_effect(move |_| state.sync_document());
 
-        for entry in state.dictionary.get() {
-            wtr.serialize(entry).unwrap()
+    let history_storage = storage.clone();
+    create_effect(move |_| {
+        let current = state.current_document.get();
+        state
+            .revisions
+            .set(history::load(&history_storage, current));
+    });
+
+    let history_storage = storage.clone();
+    create_effect(move |_| {
+        let revisions = state.revisions.get();
+        history::save(
+            &history_storage,
+            state.current_document.get_untracked(),
+            &revisions,
+        );
+    });
+
+    // Only adding or deleting documents can orphan revisions, so the keys
+    // are scanned when the set of ids changes rather than on every edit.
+    let document_ids = create_memo(move |_| {
+        state.documents.with(|documents| {
+            documents
+                .iter()
+                .map(|document| document.id)
+                .collect::<Vec<_>>()
+        })
+    });
+    let history_storage = storage.clone();
+    create_effect(move |_| {
+        let keys: Vec<String> = document_ids
+            .get()
+            .into_iter()
+            .map(history::storage_key)
+            .collect();
+
+        for key in history_storage.keys() {
+            let Some(id) = key.strip_prefix(history::STORAGE_PREFIX) else {
+                continue;
+            };
+            // Documents set aside as unreadable keep their revisions.
+            let set_aside = format!("{}{id}", documents::UNREADABLE_PREFIX);
+            if !keys.contains(&key) && history_storage.get_item(&set_aside).is_none() {
+                history_storage.remove_item(&key);
+            }
         }
+    });
 
-        let data = String::from_utf8(wtr.into_inner().unwrap()).unwrap();
-        let file = Blob::new(&*data);
-        let object_url = ObjectUrl::from(file);
+    Interval::new(30_000, move || state.autosave()).forget();
+
+    // Only edits count towards the writing log, not switching documents,
+    // changing what is counted or replacing the text by Clear, an import,
+    // undo or a restore.
+    create_effect(
+        move |previous: Option<(u64, SourceFormat, MarkupOptions, u64, usize)>| {
+            let current = (
+                state.current_document.get(),
+                state.source_format.get(),
+                state.markup_options.get(),
+                state.text_replacements.get(),
+                state.word_count(),
+            );
+
+            if let Some((document, source_format, markup_options, replacements, words)) = previous {
+                if (document, source_format, markup_options, replacements)
+                    == (current.0, current.1, current.2, current.3)
+                    && words != current.4
+                {
+                    state
+                        .writing_log
+                        .update(|log| log.record(current.4 as i64 - words as i64, Date::now()));
+                }
+            }
 
-        download(&object_url, "results.csv")
-    };
+            current
+        },
+    );
 
-    create_effect(move |_| {
-        let storage = window().local_storage().unwrap().unwrap();
-        let _ = state.text.get();
-        let _ = state.match_case.get();
-        get_result();
+    let sessions_storage = storage.clone();
+    create_effect(move |_| sessions::save(&sessions_storage, &state.writing_log.get()));
 
-        storage
-            .set_item(
-                "statistics_options",
-                &json!(state.statistics_options.get()).to_string(),
-            )
-            .unwrap();
+    let undo_storage = storage.clone();
+    create_effect(move |_| undo::save(&undo_storage, &state.undo_stack.get()));
+
+    create_effect(move |previous: Option<Vec<Document>>| {
+        let documents = state.documents.get();
+        documents::save(
+            &storage,
+            &documents,
+            previous.as_deref().unwrap_or_default(),
+            state.current_document.get(),
+        );
+        documents
     });
 
     view! {
@@ -631,7 +1821,31 @@ pub fn App() -> impl IntoView {
 
             <div id="overlay" class="fixed hidden z-40 w-screen h-screen inset-0 bg-gray-900 bg-opacity-60"></div>
 
-            <OptionsDialog onbutton_done=close_options statistics_options=state.statistics_options />
+            {
+                move || {
+                    let _ = state.current_document.get();
+                    view! { <OptionsDialog onbutton_done=close_options statistics_options=state.statistics_options /> }
+                }
+            }
+
+            <DocumentLibrary open=library_open />
+            <HistoryDialog open=history_open />
+            <UndoToast />
+            <Teleprompter open=teleprompter_open />
+            <CompareDialog open=compare_open />
+            <TranslationDialog open=translation_open />
+
+            {
+                move || state.storage_error.get().map(|error| view! {
+                    <div class="flex justify-between items-center p-2 mb-2 bg-red-200 text-red-900 rounded-md dark:bg-red-900 dark:text-red-100" role="alert">
+                        <span>{error}</span>
+                        <button type="button" class="p-2 rounded cursor-pointer hover:bg-red-300 dark:hover:bg-red-800" on:click=move |_| state.storage_error.set(None)>
+                            <i class="fa-solid fa-xmark"></i>
+                            <span class="sr-only">{ "Dismiss" }</span>
+                        </button>
+                    </div>
+                })
+            }
 
             <div class="space-y-7">
                 <div class="lg:flex bg-gray-200 p-2 mb-6 mt-auto mb-auto dark:bg-gray-800">
@@ -639,11 +1853,21 @@ pub fn App() -> impl IntoView {
                         <div class="flex items-center justify-between px-3 py-2 border-b dark:border-gray-600">
                             <div class="flex flex-wrap items-center divide-gray-200 sm:divide-x sm:rtl:divide-x-reverse dark:divide-gray-600">
                                 <div class="flex items-center space-x-1 rtl:space-x-reverse sm:pe-4">
+                                    <button type="button" class="p-2 text-gray-500 rounded cursor-pointer hover:text-gray-900 hover:bg-gray-100 dark:text-gray-400 dark:hover:text-white dark:hover:bg-gray-600" on:click=move |_| library_open.set(true)>
+                                        <i class="fa-solid fa-folder-open w-4 h-4"></i>
+                                        <span class="sr-only">{ "Documents" }</span>
+                                    </button>
+
                                     <button type="button" class="p-2 text-gray-500 rounded cursor-pointer hover:text-gray-900 hover:bg-gray-100 dark:text-gray-400 dark:hover:text-white dark:hover:bg-gray-600" on:click=clear_input>
                                         <img class="w-4 h-4" src="public/icons/clear.svg" alt="Clear" height="48" width="48" />
                                         <span class="sr-only">{ "Clear Input" }</span>
                                     </button>
 
+                                    <button type="button" class="p-2 text-gray-500 rounded cursor-pointer hover:text-gray-900 hover:bg-gray-100 dark:text-gray-400 dark:hover:text-white dark:hover:bg-gray-600 disabled:opacity-50 disabled:cursor-default" disabled=move || state.undo_stack.get().is_empty() on:click=move |_| state.undo()>
+                                        <i class="fa-solid fa-rotate-left w-4 h-4"></i>
+                                        <span class="sr-only">{ "Undo" }</span>
+                                    </button>
+
                                     {
                                         move || view! {
                                             <button type="button" class=format!("p-2 text-gray-500 rounded cursor-pointer hover:text-gray-900 hover:bg-gray-100 dark:text-gray-400 dark:hover:text-white dark:hover:bg-gray-600 {}", if state.match_case.get() { "bg-gray-50 text-gray-200" } else { "" }) on:click=toggle_case_insensitive>
@@ -653,11 +1877,43 @@ pub fn App() -> impl IntoView {
                                         }
                                     }
 
+                                    <button type="button" class="p-2 text-gray-500 rounded cursor-pointer hover:text-gray-900 hover:bg-gray-100 dark:text-gray-400 dark:hover:text-white dark:hover:bg-gray-600" on:click=move |_| history_open.set(true)>
+                                        <i class="fa-solid fa-clock-rotate-left w-4 h-4"></i>
+                                        <span class="sr-only">{ "History" }</span>
+                                    </button>
+
+                                    <button type="button" class="p-2 text-gray-500 rounded cursor-pointer hover:text-gray-900 hover:bg-gray-100 dark:text-gray-400 dark:hover:text-white dark:hover:bg-gray-600" on:click=move |_| compare_open.set(true)>
+                                        <i class="fa-solid fa-code-compare w-4 h-4"></i>
+                                        <span class="sr-only">{ "Compare" }</span>
+                                    </button>
+
+                                    <button type="button" class="p-2 text-gray-500 rounded cursor-pointer hover:text-gray-900 hover:bg-gray-100 dark:text-gray-400 dark:hover:text-white dark:hover:bg-gray-600" on:click=move |_| translation_open.set(true)>
+                                        <i class="fa-solid fa-language w-4 h-4"></i>
+                                        <span class="sr-only">{ "Translation" }</span>
+                                    </button>
+
+                                    <button type="button" class="p-2 text-gray-500 rounded cursor-pointer hover:text-gray-900 hover:bg-gray-100 dark:text-gray-400 dark:hover:text-white dark:hover:bg-gray-600" on:click=move |_| teleprompter_open.set(true)>
+                                        <i class="fa-solid fa-display w-4 h-4"></i>
+                                        <span class="sr-only">{ "Teleprompter" }</span>
+                                    </button>
+
                                     <button type="button" class="p-2 text-gray-500 rounded cursor-pointer hover:text-gray-900 hover:bg-gray-100 dark:text-gray-400 dark:hover:text-white dark:hover:bg-gray-600" on:click=open_options>
                                         <img class="w-4 h-4" src="public/icons/settings.svg" alt="Settings" height="48" width="48" />
                                         <span class="sr-only">{ "Settings" }</span>
                                     </button>
 
+                                    <button type="button" class="p-2 text-gray-500 rounded cursor-pointer hover:text-gray-900 hover:bg-gray-100 dark:text-gray-400 dark:hover:text-white dark:hover:bg-gray-600" on:click=open_subtitles>
+                                        <i class="fa-solid fa-closed-captioning w-4 h-4"></i>
+                                        <span class="sr-only">{ "Import Subtitles" }</span>
+                                    </button>
+                                    <input _ref=subtitle_input type="file" class="hidden" accept=".srt,.vtt" on:change=import_subtitles />
+
+                                    <button type="button" class="p-2 text-gray-500 rounded cursor-pointer hover:text-gray-900 hover:bg-gray-100 dark:text-gray-400 dark:hover:text-white dark:hover:bg-gray-600" on:click=open_quote_files>
+                                        <i class="fa-solid fa-file-invoice-dollar w-4 h-4"></i>
+                                        <span class="sr-only">{ "Import Files for Word Count Analysis" }</span>
+                                    </button>
+                                    <input _ref=quote_input type="file" class="hidden" multiple accept=".xlf,.xliff,.po,.pot" on:change=import_quote_files />
+
                                     <button type="button" class="p-2 text-gray-500 rounded cursor-pointer hover:text-gray-900 hover:bg-gray-100 dark:text-gray-400 dark:hover:text-white dark:hover:bg-gray-600" on:click=export_csv>
                                         <img class="w-4 h-4" src="public/icons/download.svg" alt="Export CSV" height="48" width="48" />
                                         <span class="sr-only">{ "Export CSV" }</span>
@@ -667,16 +1923,31 @@ pub fn App() -> impl IntoView {
                         </div>
                         <div class="lg:flex lg:flex-row">
                             <div class="lg:w-8/12 p-2">
-                                <textarea
-                                class="block w-full h-96 lg:h-full p-2 mb-1 border-2 border-gray-400 rounded-lg focus:outline-none dark:bg-black"
-                                placeholder="Enter text here"
-                                prop:value={move || state.text.get()}
-                                on:input=update_text></textarea>
+                                <div class="relative h-96 lg:h-full mb-1 bg-white rounded-lg dark:bg-black">
+                                    <EditorHighlights layer=highlight_layer />
+                                    <textarea
+                                    id=EDITOR_ID
+                                    class="relative block w-full h-full p-2 border-2 border-gray-400 rounded-lg focus:outline-none bg-transparent"
+                                    style="scrollbar-gutter: stable"
+                                    placeholder="Enter text here"
+                                    prop:value={move || state.text.get()}
+                                    on:input=update_text
+                                    on:scroll=sync_highlights></textarea>
+                                </div>
                             </div>
                             <div class="lg:w-4/12 p-2">
                                 {
                                     move || view! { <StatisticsOptionsPanel />}
                                 }
+                                <OutlinePanel />
+                                <ScriptPanel />
+                                <SubtitlesPanel />
+                                <QuotePanel />
+                                <RepetitionPanel />
+                                <ParagraphPanel />
+                                <SentencePanel />
+                                <DuplicatePanel />
+                                <WritingActivityPanel />
                                 <div class="bg-white p-3 rounded-md border-2 border-gray-700 dark:bg-gray-800">
                                     <div class="text-3xl mt-2 mb-4 h5">{"Keyword Density"}</div>
                                     <div class="relative overflow-auto h-full max-h-56 mb-4 border-b-2">
@@ -714,6 +1985,7 @@ pub fn App() -> impl IntoView {
                                         }}
                                     </div>
                                 </div>
+                                <CharacterFrequencyPanel />
                             </div>
                         </div>
                     </div>
@@ -723,6 +1995,29 @@ pub fn App() -> impl IntoView {
     }
 }
 
+/// Writes `records` below `headers` and downloads them as `file_name`.
+pub(crate) fn download_csv<R: Serialize>(
+    file_name: &str,
+    headers: &[&str],
+    records: impl IntoIterator<Item = R>,
+) {
+    let result = Vec::new();
+    let mut wtr = csv::Writer::from_writer(result);
+
+    // We still need to write headers manually.
+    wtr.write_record(headers).unwrap();
+
+    for record in records {
+        wtr.serialize(record).unwrap()
+    }
+
+    let data = String::from_utf8(wtr.into_inner().unwrap()).unwrap();
+    let file = Blob::new(&*data);
+    let object_url = ObjectUrl::from(file);
+
+    download(&object_url, file_name)
+}
+
 fn download(path: &str, file_name: &str) {
     let anchor = document().create_element("a").unwrap();
     anchor.set_attribute("href", path).unwrap();
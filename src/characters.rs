@@ -0,0 +1,147 @@
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::sync::LazyLock;
+use unicode_segmentation::UnicodeSegmentation;
+use unicode_width::UnicodeWidthStr;
+
+static WHITESPACE_REGEX: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"^\p{White_Space}").unwrap());
+static PUNCTUATION_REGEX: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"^\p{P}").unwrap());
+static DIGIT_REGEX: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"^\p{Nd}").unwrap());
+static SYMBOL_REGEX: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"^\p{S}").unwrap());
+static LETTER_REGEX: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"^\p{L}").unwrap());
+static UPPERCASE_REGEX: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"^\p{Lu}").unwrap());
+static LOWERCASE_REGEX: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"^\p{Ll}").unwrap());
+/// Pictographs shown as emoji: those that are by default, and others such
+/// as ©, ® or ™ only when a variation selector or skin tone asks for it.
+static EMOJI_REGEX: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(
+        r"^(?:[\p{Emoji_Presentation}\p{Regional_Indicator}]|\p{Extended_Pictographic}[\x{FE0F}\p{Emoji_Modifier}]|[#*0-9]\x{FE0F}?\x{20E3})",
+    )
+    .unwrap()
+});
+
+/// What counts as one character.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum CharacterUnit {
+    /// User-perceived characters, so that an emoji with a skin tone or a
+    /// family emoji counts once.
+    #[default]
+    Graphemes,
+    CodePoints,
+    Utf8Bytes,
+    /// What JavaScript and many databases count.
+    Utf16Units,
+    /// Terminal columns, with wide East Asian characters taking two.
+    VisibleWidth,
+}
+
+impl CharacterUnit {
+    pub const ALL: [Self; 5] = [
+        Self::Graphemes,
+        Self::CodePoints,
+        Self::Utf8Bytes,
+        Self::Utf16Units,
+        Self::VisibleWidth,
+    ];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::Graphemes => "Graphemes",
+            Self::CodePoints => "Code Points",
+            Self::Utf8Bytes => "UTF-8 Bytes",
+            Self::Utf16Units => "UTF-16 Units",
+            Self::VisibleWidth => "Visible Width",
+        }
+    }
+
+    pub fn count(self, text: &str) -> usize {
+        match self {
+            Self::Graphemes => text.graphemes(true).count(),
+            Self::CodePoints => text.chars().count(),
+            Self::Utf8Bytes => text.len(),
+            Self::Utf16Units => text.encode_utf16().count(),
+            Self::VisibleWidth => text.lines().map(UnicodeWidthStr::width).sum(),
+        }
+    }
+}
+
+/// A Unicode category that a grapheme belongs to when its first code point
+/// does, so that an accented letter or an emoji sequence counts as a whole.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CharacterClass {
+    Whitespace,
+    Punctuation,
+    Digits,
+    Symbols,
+    Letters,
+    Uppercase,
+    Lowercase,
+    Emoji,
+}
+
+impl CharacterClass {
+    fn regex(self) -> &'static Regex {
+        match self {
+            Self::Whitespace => &WHITESPACE_REGEX,
+            Self::Punctuation => &PUNCTUATION_REGEX,
+            Self::Digits => &DIGIT_REGEX,
+            Self::Symbols => &SYMBOL_REGEX,
+            Self::Letters => &LETTER_REGEX,
+            Self::Uppercase => &UPPERCASE_REGEX,
+            Self::Lowercase => &LOWERCASE_REGEX,
+            Self::Emoji => &EMOJI_REGEX,
+        }
+    }
+
+    pub fn contains(self, grapheme: &str) -> bool {
+        self.regex().is_match(grapheme)
+    }
+
+    /// The characters of `text` in this class, measured in `unit`.
+    pub fn count(self, text: &str, unit: CharacterUnit) -> usize {
+        count_graphemes(text, unit, |grapheme| self.contains(grapheme))
+    }
+}
+
+/// The classes left out of the "no spaces" character count.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CharacterExclusions {
+    pub whitespace: bool,
+    pub punctuation: bool,
+    pub digits: bool,
+    pub symbols: bool,
+}
+
+impl Default for CharacterExclusions {
+    fn default() -> Self {
+        Self {
+            whitespace: true,
+            punctuation: false,
+            digits: false,
+            symbols: false,
+        }
+    }
+}
+
+impl CharacterExclusions {
+    pub fn excludes(&self, grapheme: &str) -> bool {
+        (self.whitespace && CharacterClass::Whitespace.contains(grapheme))
+            || (self.punctuation && CharacterClass::Punctuation.contains(grapheme))
+            || (self.digits && CharacterClass::Digits.contains(grapheme))
+            || (self.symbols && CharacterClass::Symbols.contains(grapheme))
+    }
+
+    /// The characters of `text` outside the excluded classes, measured in
+    /// `unit`.
+    pub fn count(&self, text: &str, unit: CharacterUnit) -> usize {
+        count_graphemes(text, unit, |grapheme| !self.excludes(grapheme))
+    }
+}
+
+fn count_graphemes(text: &str, unit: CharacterUnit, include: impl Fn(&str) -> bool) -> usize {
+    text.graphemes(true)
+        .filter(|grapheme| include(grapheme))
+        .map(|grapheme| unit.count(grapheme))
+        .sum()
+}
@@ -0,0 +1,77 @@
+use std::collections::{BTreeMap, HashSet};
+
+use crate::{app::WORD_REGEX, repetition::STOP_WORDS};
+
+/// How often a word occurs in each of the two compared texts.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct KeywordChange {
+    pub word: String,
+    pub before: usize,
+    pub after: usize,
+}
+
+impl KeywordChange {
+    pub fn delta(&self) -> i64 {
+        self.after as i64 - self.before as i64
+    }
+}
+
+pub fn word_counts(text: &str, match_case: bool) -> BTreeMap<String, usize> {
+    let mut counts = BTreeMap::new();
+
+    for word in WORD_REGEX.find_iter(text) {
+        let word = if match_case {
+            word.as_str().to_owned()
+        } else {
+            word.as_str().to_lowercase()
+        };
+
+        *counts.entry(word).or_insert(0) += 1;
+    }
+
+    counts
+}
+
+/// The words whose frequency differs between `before` and `after`, the
+/// largest changes first. Stop words are left out.
+pub fn keyword_changes(
+    before: &BTreeMap<String, usize>,
+    after: &BTreeMap<String, usize>,
+) -> Vec<KeywordChange> {
+    let mut changes: Vec<KeywordChange> = before
+        .keys()
+        .chain(after.keys().filter(|word| !before.contains_key(*word)))
+        .map(|word| KeywordChange {
+            word: word.clone(),
+            before: before.get(word).copied().unwrap_or_default(),
+            after: after.get(word).copied().unwrap_or_default(),
+        })
+        .filter(|change| {
+            change.before != change.after
+                && !STOP_WORDS.contains(change.word.to_lowercase().as_str())
+        })
+        .collect();
+
+    changes.sort_by(|a, b| {
+        b.delta()
+            .abs()
+            .cmp(&a.delta().abs())
+            .then_with(|| a.word.cmp(&b.word))
+    });
+
+    changes
+}
+
+/// The Jaccard index of the two vocabularies: the share of distinct words
+/// used in either text that are used in both. `None` when neither text has
+/// any words.
+pub fn vocabulary_overlap(
+    before: &BTreeMap<String, usize>,
+    after: &BTreeMap<String, usize>,
+) -> Option<f64> {
+    let before: HashSet<&String> = before.keys().collect();
+    let after: HashSet<&String> = after.keys().collect();
+    let union = before.union(&after).count();
+
+    (union > 0).then(|| before.intersection(&after).count() as f64 / union as f64)
+}
@@ -0,0 +1,179 @@
+use regex::Regex;
+use std::{sync::LazyLock, time::Duration};
+
+use crate::{
+    app::WORD_REGEX,
+    markup::{
+        self, MarkupOptions, SourceFormat, HTML_CODE_REGEXES, HTML_IMAGE_REGEX,
+        HTML_TABLE_ROW_REGEX, MARKDOWN_FENCE_REGEX, MARKDOWN_IMAGE_REGEX,
+        MARKDOWN_TABLE_DIVIDER_REGEX, MARKDOWN_TABLE_ROW_REGEX,
+    },
+    readability::Readability,
+};
+
+/// Flesch reading ease of average prose; harder text is read more slowly
+/// and easier text more quickly than the flat estimate.
+const AVERAGE_READING_EASE: f64 = 60.0;
+const MIN_DIFFICULTY_FACTOR: f64 = 0.8;
+const MAX_DIFFICULTY_FACTOR: f64 = 1.4;
+
+/// Seconds spent on the first image, each further one taking a second less
+/// down to `MIN_IMAGE_SECONDS`.
+const FIRST_IMAGE_SECONDS: f64 = 12.0;
+const MIN_IMAGE_SECONDS: f64 = 3.0;
+const CODE_LINE_SECONDS: f64 = 4.0;
+const NUMBER_SECONDS: f64 = 0.5;
+const TABLE_ROW_SECONDS: f64 = 3.0;
+
+static NUMBER_REGEX: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"\b\d+(?:[.,]\d+)*\b").unwrap());
+
+/// A reading time that accounts for how hard the text is and for the
+/// parts that are not read like prose. Each component is in seconds.
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub struct ReadingEstimate {
+    /// The flat estimate from the reading profile.
+    pub base: f64,
+    pub reading_ease: f64,
+    /// Added for hard text, negative for easy text.
+    pub difficulty: f64,
+    pub image_count: usize,
+    pub images: f64,
+    pub code_line_count: usize,
+    pub code: f64,
+    pub number_count: usize,
+    pub numbers: f64,
+    pub table_row_count: usize,
+    pub tables: f64,
+}
+
+impl ReadingEstimate {
+    /// Estimates from the raw `text`, for images, code and tables, and its
+    /// `prose`, for difficulty and numbers. Images, code and tables are only
+    /// told apart in Markdown and HTML, and the words they hold are taken out
+    /// of `base` so that they are not read twice.
+    pub fn of(
+        text: &str,
+        prose: &str,
+        format: SourceFormat,
+        options: MarkupOptions,
+        base: Duration,
+    ) -> Self {
+        let reading_ease = Readability::of(prose).reading_ease();
+        let factor = if prose.trim().is_empty() {
+            1.0
+        } else {
+            (1.0 + (AVERAGE_READING_EASE - reading_ease) / 150.0)
+                .clamp(MIN_DIFFICULTY_FACTOR, MAX_DIFFICULTY_FACTOR)
+        };
+
+        let parts = match format {
+            SourceFormat::Markdown => Parts::markdown(text),
+            SourceFormat::Html => Parts::html(text),
+            SourceFormat::Plain | SourceFormat::Latex | SourceFormat::AsciiDoc => Parts::default(),
+        };
+
+        let mut base = base.as_secs_f64();
+        let prose_words = WORD_REGEX.find_iter(prose).count();
+
+        if parts.image_count + parts.table_row_count > 0 && prose_words > 0 {
+            let remaining = markup::prose(&parts.remaining, format, options);
+            let remaining_words = WORD_REGEX.find_iter(&remaining).count();
+            base *= (remaining_words as f64 / prose_words as f64).min(1.0);
+        }
+
+        let images = (0..parts.image_count)
+            .map(|index| (FIRST_IMAGE_SECONDS - index as f64).max(MIN_IMAGE_SECONDS))
+            .sum();
+        let number_count = NUMBER_REGEX.find_iter(prose).count();
+
+        Self {
+            base,
+            reading_ease,
+            difficulty: base * (factor - 1.0),
+            image_count: parts.image_count,
+            images,
+            code_line_count: parts.code_line_count,
+            code: parts.code_line_count as f64 * CODE_LINE_SECONDS,
+            number_count,
+            numbers: number_count as f64 * NUMBER_SECONDS,
+            table_row_count: parts.table_row_count,
+            tables: parts.table_row_count as f64 * TABLE_ROW_SECONDS,
+        }
+    }
+
+    pub fn total(&self) -> Duration {
+        let seconds =
+            self.base + self.difficulty + self.images + self.code + self.numbers + self.tables;
+
+        Duration::from_secs_f64(seconds.max(0.0))
+    }
+}
+
+/// The parts of a marked-up text that are not read like prose, and the text
+/// left once images and tables are taken out. Code is already left out of
+/// the prose.
+#[derive(Debug, Default)]
+struct Parts {
+    image_count: usize,
+    code_line_count: usize,
+    table_row_count: usize,
+    remaining: String,
+}
+
+impl Parts {
+    fn markdown(text: &str) -> Self {
+        let text = text.replace("\r\n", "\n");
+        // The fence lines themselves are not code.
+        let code_line_count = MARKDOWN_FENCE_REGEX
+            .find_iter(&text)
+            .map(|block| non_empty_lines(block.as_str()).saturating_sub(2))
+            .sum();
+        let table_row_count = MARKDOWN_TABLE_ROW_REGEX
+            .find_iter(&text)
+            .filter(|row| !MARKDOWN_TABLE_DIVIDER_REGEX.is_match(row.as_str()))
+            .count();
+        let image_count = MARKDOWN_IMAGE_REGEX.find_iter(&text).count();
+        let remaining = MARKDOWN_TABLE_ROW_REGEX.replace_all(&text, "");
+        let remaining = MARKDOWN_IMAGE_REGEX.replace_all(&remaining, "");
+
+        Self {
+            image_count,
+            code_line_count,
+            table_row_count,
+            remaining: remaining.into_owned(),
+        }
+    }
+
+    fn html(text: &str) -> Self {
+        let mut remaining = text.to_owned();
+        let mut code_line_count = 0;
+
+        // Removed in turn, as the prose does, with only `<pre>` blocks read
+        // as code; scripts, styles and inline code are not.
+        for regex in HTML_CODE_REGEXES.iter() {
+            code_line_count += regex
+                .find_iter(&remaining)
+                .filter(|code| code.as_str()[..4].eq_ignore_ascii_case("<pre"))
+                .map(|code| non_empty_lines(code.as_str()))
+                .sum::<usize>();
+            remaining = regex.replace_all(&remaining, "").into_owned();
+        }
+
+        let image_count = HTML_IMAGE_REGEX.find_iter(&remaining).count();
+        let table_row_count = HTML_TABLE_ROW_REGEX.find_iter(&remaining).count();
+        let remaining = HTML_TABLE_ROW_REGEX.replace_all(&remaining, "");
+        let remaining = HTML_IMAGE_REGEX.replace_all(&remaining, "");
+
+        Self {
+            image_count,
+            code_line_count,
+            table_row_count,
+            remaining: remaining.into_owned(),
+        }
+    }
+}
+
+fn non_empty_lines(text: &str) -> usize {
+    text.lines().filter(|line| !line.trim().is_empty()).count()
+}
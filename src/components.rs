@@ -1,11 +1,38 @@
-use std::time::Duration;
+use std::{cell::RefCell, rc::Rc, time::Duration};
 
+use gloo::{
+    file::{futures::read_as_text, File},
+    timers::callback::{Interval, Timeout},
+};
+use js_sys::Date;
 use leptos::{
-    component, create_effect, create_node_ref, event_target_checked, html::Input, use_context,
-    view, IntoView, RwSignal, SignalGet, SignalSet,
+    component, create_effect, create_memo, create_node_ref, create_rw_signal, ev,
+    ev::MouseEvent,
+    event_target_checked, event_target_value,
+    html::{Div, Input},
+    on_cleanup, spawn_local, use_context, view, window, window_event_listener, IntoView, NodeRef,
+    RwSignal, SignalGet, SignalGetUntracked, SignalSet, SignalUpdate, SignalWith, View,
 };
 
-use crate::app::{GlobalState, StatisticOption};
+use crate::{
+    app::{download_csv, GlobalState, StatisticOption, WORD_REGEX},
+    characters::{CharacterClass, CharacterUnit},
+    compare,
+    complexity::ReadingEstimate,
+    editor,
+    frequency::{self, ENGLISH_MAXIMUM},
+    goals::{Goal, GoalMetric, GoalStatus},
+    history::{self, Change, Snapshot},
+    outline::Section,
+    platforms::Platform,
+    quote::{self, DiscountGrid, WordCounts},
+    repetition::RepetitionKind,
+    sentences::{self, SentenceColumn},
+    sessions,
+    speed::{Activity, SpeedProfile, MAX_PAUSE},
+    storage::Storage,
+    translation::Translation,
+};
 
 #[component]
 pub fn statistics_options_panel() -> impl IntoView {
@@ -13,6 +40,12 @@ pub fn statistics_options_panel() -> impl IntoView {
     view! {
         <div class="mb-4 bg-white p-3 rounded-md border-2 border-gray-700 text-gray-500 dark:bg-gray-800 overflow-y max-h-75">
             <div class="h2 text-3xl text-black mt-2 mb-4 dark:text-white">{"Statistics"}</div>
+            {
+                move || state.platform.get().map(|platform| platform_gauge(platform, &state.content()))
+            }
+            {
+                move || state.goal.get().filter(|goal| goal.target > 0 || goal.upper_bound().is_some()).map(|goal| goal_progress(state, goal))
+            }
             {
                 move || {
                     let mut row = Vec::new();
@@ -57,19 +90,33 @@ pub fn statistics_options_panel() -> impl IntoView {
                                                 <span class="text-4xl text-black dark:text-white">{state.paragraph_count()}</span>
                                             </>
                                         },
-                                        StatisticOption::ReadingTime(n) => view! {
-                                            <>
-                                                <div class="uppercase text-xs whitespace-nowrap">
-                                                    {"Reading Time"}
-                                                    <span title=format!("Based on {n} words per minute") class="inline-block">
-                                                        <i class="fa-solid fa-circle-question"></i>
-                                                    </span>
-                                                </div>
-                                                <div class="flex flex-nowrap">
-                                                    <span class="text-3xl text-black dark:text-white">{move || format_duration( calculate_duration(state.word_count(), n as u32))}</span>
-                                                </div>
-                                            </>
-                                        },
+                                        StatisticOption::ReadingTime => {
+                                            let profile = state.speed_profiles.get().get(Activity::Reading).clone();
+                                            view! {
+                                                <>
+                                                    <div class="uppercase text-xs whitespace-nowrap">
+                                                        {"Reading Time"}
+                                                        <span title=speed_profile_summary(&profile, Activity::Reading) class="inline-block">
+                                                            <i class="fa-solid fa-circle-question"></i>
+                                                        </span>
+                                                    </div>
+                                                    <div class="flex flex-nowrap">
+                                                        <span class="text-3xl text-black dark:text-white">{move || format_duration(state.activity_time(Activity::Reading))}</span>
+                                                    </div>
+                                                    {
+                                                        move || state.adjust_reading_time.get().then(|| {
+                                                            let estimate = state.adjusted_reading_time();
+                                                            view! {
+                                                                <div class="text-sm whitespace-nowrap" title=reading_estimate_breakdown(&estimate)>
+                                                                    {format!("≈ {} adjusted ", format_duration_short(estimate.total()))}
+                                                                    <i class="fa-solid fa-circle-question"></i>
+                                                                </div>
+                                                            }
+                                                        })
+                                                    }
+                                                </>
+                                            }
+                                        }
                                         StatisticOption::Sentences => view! {
                                             <>
                                                 <div class="uppercase text-xs">{"Sentences"}</div>
@@ -80,12 +127,14 @@ pub fn statistics_options_panel() -> impl IntoView {
                                             <>
                                                 <div class="uppercase text-xs">{"Longest Sentence (Words)"}</div>
                                                 <span class="text-4xl text-black dark:text-white">{state.show_longest_sentence_words_count()}</span>
+                                                {sentence_jump(state, true)}
                                             </>
                                         },
                                         StatisticOption::ShortestSentenceWords => view! {
                                             <>
                                                 <div class="uppercase text-xs">{"Shortest Sentence (Words)"}</div>
                                                 <span class="text-4xl text-black dark:text-white">{state.show_shortest_sentence_words_count()}</span>
+                                                {sentence_jump(state, false)}
                                             </>
                                         },
                                         StatisticOption::AvgSentenceWords => view! {
@@ -106,32 +155,38 @@ pub fn statistics_options_panel() -> impl IntoView {
                                                 <span class="text-4xl text-black dark:text-white">{format!("{:.1}", state.avg_word_count())}</span>
                                             </>
                                         },
-                                        StatisticOption::SpeakingTime(n) => view! {
-                                            <>
-                                                <div class="uppercase text-xs whitespace-nowrap">
-                                                    {"Speaking Time"}
-                                                    <span title=format!("Based on {n} words per minute") class="inline-block">
-                                                        <i class="fa-solid fa-circle-question"></i>
-                                                    </span>
-                                                </div>
-                                                <div class="flex flex-nowrap">
-                                                    <span class="text-3xl text-black dark:text-white">{move || format_duration( calculate_duration(state.word_count(), n as u32))}</span>
-                                                </div>
-                                            </>
-                                        },
-                                        StatisticOption::HandWritingTime(n) => view! {
-                                            <>
-                                                <div class="uppercase text-xs whitespace-nowrap">
-                                                    {"Hand Writing Time"}
-                                                    <span title=format!("Based on an average hand writing speed of {n} characters per minute") class="inline-block">
-                                                        <i class="fa-solid fa-circle-question"></i>
-                                                    </span>
-                                                </div>
-                                                <div class="flex flex-nowrap">
-                                                    <span class="text-3xl text-black dark:text-white">{move || format_duration( calculate_duration(state.text.get().len(), n as u32))}</span>
-                                                </div>
-                                            </>
-                                        },
+                                        StatisticOption::SpeakingTime => {
+                                            let profile = state.speed_profiles.get().get(Activity::Speaking).clone();
+                                            view! {
+                                                <>
+                                                    <div class="uppercase text-xs whitespace-nowrap">
+                                                        {"Speaking Time"}
+                                                        <span title=speed_profile_summary(&profile, Activity::Speaking) class="inline-block">
+                                                            <i class="fa-solid fa-circle-question"></i>
+                                                        </span>
+                                                    </div>
+                                                    <div class="flex flex-nowrap">
+                                                        <span class="text-3xl text-black dark:text-white">{move || format_duration(state.activity_time(Activity::Speaking))}</span>
+                                                    </div>
+                                                </>
+                                            }
+                                        }
+                                        StatisticOption::HandWritingTime => {
+                                            let profile = state.speed_profiles.get().get(Activity::HandWriting).clone();
+                                            view! {
+                                                <>
+                                                    <div class="uppercase text-xs whitespace-nowrap">
+                                                        {"Hand Writing Time"}
+                                                        <span title=speed_profile_summary(&profile, Activity::HandWriting) class="inline-block">
+                                                            <i class="fa-solid fa-circle-question"></i>
+                                                        </span>
+                                                    </div>
+                                                    <div class="flex flex-nowrap">
+                                                        <span class="text-3xl text-black dark:text-white">{move || format_duration(state.activity_time(Activity::HandWriting))}</span>
+                                                    </div>
+                                                </>
+                                            }
+                                        }
                                         StatisticOption::UniqueWords => view! {
                                             <>
                                                 <div class="uppercase text-xs">{"Unique Words"}</div>
@@ -144,6 +199,106 @@ pub fn statistics_options_panel() -> impl IntoView {
                                                 <span class="text-4xl text-black dark:text-white">{state.word_count()}</span>
                                             </>
                                         },
+                                        StatisticOption::SubtitleWordsPerMinute => view! {
+                                            <>
+                                                <div class="uppercase text-xs">{"Subtitle WPM"}</div>
+                                                <span class="text-4xl text-black dark:text-white">{format!("{:.0}", state.subtitle_words_per_minute())}</span>
+                                            </>
+                                        },
+                                        StatisticOption::SubtitleCharactersPerSecond => view! {
+                                            <>
+                                                <div class="uppercase text-xs">{"Subtitle CPS"}</div>
+                                                <span class="text-4xl text-black dark:text-white">{format!("{:.1}", state.subtitle_characters_per_second())}</span>
+                                            </>
+                                        },
+                                        StatisticOption::Graphemes => view! {
+                                            <>
+                                                <div class="uppercase text-xs">{CharacterUnit::Graphemes.label()}</div>
+                                                <span class="text-4xl text-black dark:text-white">{state.character_count_in(CharacterUnit::Graphemes)}</span>
+                                            </>
+                                        },
+                                        StatisticOption::CodePoints => view! {
+                                            <>
+                                                <div class="uppercase text-xs">{CharacterUnit::CodePoints.label()}</div>
+                                                <span class="text-4xl text-black dark:text-white">{state.character_count_in(CharacterUnit::CodePoints)}</span>
+                                            </>
+                                        },
+                                        StatisticOption::Utf8Bytes => view! {
+                                            <>
+                                                <div class="uppercase text-xs">{CharacterUnit::Utf8Bytes.label()}</div>
+                                                <span class="text-4xl text-black dark:text-white">{state.character_count_in(CharacterUnit::Utf8Bytes)}</span>
+                                            </>
+                                        },
+                                        StatisticOption::Utf16Units => view! {
+                                            <>
+                                                <div class="uppercase text-xs">{CharacterUnit::Utf16Units.label()}</div>
+                                                <span class="text-4xl text-black dark:text-white">{state.character_count_in(CharacterUnit::Utf16Units)}</span>
+                                            </>
+                                        },
+                                        StatisticOption::VisibleWidth => view! {
+                                            <>
+                                                <div class="uppercase text-xs">{CharacterUnit::VisibleWidth.label()}</div>
+                                                <span class="text-4xl text-black dark:text-white">{state.character_count_in(CharacterUnit::VisibleWidth)}</span>
+                                            </>
+                                        },
+                                        StatisticOption::Letters => view! {
+                                            <>
+                                                <div class="uppercase text-xs">{"Letters"}</div>
+                                                <span class="text-4xl text-black dark:text-white">{state.class_count(CharacterClass::Letters)}</span>
+                                            </>
+                                        },
+                                        StatisticOption::Digits => view! {
+                                            <>
+                                                <div class="uppercase text-xs">{"Digits"}</div>
+                                                <span class="text-4xl text-black dark:text-white">{state.class_count(CharacterClass::Digits)}</span>
+                                            </>
+                                        },
+                                        StatisticOption::PunctuationMarks => view! {
+                                            <>
+                                                <div class="uppercase text-xs">{"Punctuation Marks"}</div>
+                                                <span class="text-4xl text-black dark:text-white">{state.class_count(CharacterClass::Punctuation)}</span>
+                                            </>
+                                        },
+                                        StatisticOption::UppercaseLetters => view! {
+                                            <>
+                                                <div class="uppercase text-xs">{"Uppercase Letters"}</div>
+                                                <span class="text-4xl text-black dark:text-white">{state.class_count(CharacterClass::Uppercase)}</span>
+                                            </>
+                                        },
+                                        StatisticOption::LowercaseLetters => view! {
+                                            <>
+                                                <div class="uppercase text-xs">{"Lowercase Letters"}</div>
+                                                <span class="text-4xl text-black dark:text-white">{state.class_count(CharacterClass::Lowercase)}</span>
+                                            </>
+                                        },
+                                        StatisticOption::Duplicates => view! {
+                                            <>
+                                                <div class="uppercase text-xs" title="Sentences and paragraphs that copy an earlier one">{"Duplicates"}</div>
+                                                <span class="text-4xl text-black dark:text-white">{state.duplicate_count()}</span>
+                                            </>
+                                        },
+                                        StatisticOption::Emoji => view! {
+                                            <>
+                                                <div class="uppercase text-xs">{"Emoji"}</div>
+                                                <span class="text-4xl text-black dark:text-white">{state.class_count(CharacterClass::Emoji)}</span>
+                                            </>
+                                        },
+                                        StatisticOption::SmsSegments => {
+                                            let analysis = state.sms_analysis();
+                                            let forced_by = (!analysis.ucs2_characters.is_empty()).then(|| {
+                                                let characters: String = analysis.ucs2_characters.iter().map(|char| format!("{char:?} ")).collect();
+                                                view! { <div class="text-xs break-all" title="Characters outside the GSM-7 alphabet">{format!("UCS-2 forced by {}", characters.trim_end())}</div> }
+                                            });
+
+                                            view! {
+                                                <>
+                                                    <div class="uppercase text-xs">{"SMS Segments"}</div>
+                                                    <span class="text-4xl text-black dark:text-white">{analysis.segments}</span>
+                                                    <div class="text-xs">{format!("{} · {} left in segment", analysis.encoding.label(), analysis.remaining)}</div>
+                                                    {forced_by}
+                                                </>
+                                            }
+                                        }
                                     }
                                 }
                             </div>
@@ -173,81 +328,2105 @@ pub fn statistics_options_panel() -> impl IntoView {
     }
 }
 
+/// A button that selects the sentence with the most, or fewest, words in
+/// the editor.
+fn sentence_jump(state: GlobalState, longest: bool) -> View {
+    let jump = move |_| {
+        let text = state.text.get_untracked();
+        let mut metrics = sentences::analyze(&text, state.character_unit.get_untracked());
+        sentences::sort(&mut metrics, SentenceColumn::Words, longest);
+
+        if let Some(sentence) = metrics.first() {
+            editor::select_range(&text, sentence.range.start, sentence.range.end);
+        }
+    };
+
+    view! {
+        <button type="button" class="ml-2 p-1 text-gray-500 rounded cursor-pointer hover:text-gray-900 hover:bg-gray-100 dark:text-gray-400 dark:hover:text-white dark:hover:bg-gray-600" title="Show in the editor" on:click=jump>
+            <i class="fa-solid fa-location-crosshairs"></i>
+            <span class="sr-only">{ if longest { "Show Longest Sentence" } else { "Show Shortest Sentence" } }</span>
+        </button>
+    }
+    .into_view()
+}
+
+#[component]
+pub fn document_library(open: RwSignal<bool>) -> impl IntoView {
+    let state = use_context::<GlobalState>().unwrap_or_default();
+    let storage = use_context::<Storage>().expect("storage is provided by the app");
+
+    let rename = move |id: u64, name: String| {
+        if let Ok(Some(name)) = window().prompt_with_message_and_default("Document name", &name) {
+            if !name.trim().is_empty() {
+                state.rename_document(id, name.trim().to_owned());
+            }
+        }
+    };
+
+    let delete = move |id: u64, name: String| {
+        if window()
+            .confirm_with_message(&format!("Delete \"{name}\"?"))
+            .unwrap_or(false)
+        {
+            state.delete_document(id);
+        }
+    };
+
+    view! {
+        <div class=move || format!("fixed z-50 inset-y-0 left-0 w-80 max-w-full bg-white shadow-md p-4 overflow-auto dark:bg-slate-800 {}", if open.get() { "" } else { "hidden" })>
+            <div class="flex justify-between items-center mb-4">
+                <h1 class="text-2xl font-semibold">{"Documents"}</h1>
+                <button type="button" class="p-2 text-gray-500 rounded cursor-pointer hover:text-gray-900 hover:bg-gray-100 dark:text-gray-400 dark:hover:text-white dark:hover:bg-gray-600" on:click=move |_| open.set(false)>
+                    <i class="fa-solid fa-xmark"></i>
+                    <span class="sr-only">{ "Close Documents" }</span>
+                </button>
+            </div>
+            <button type="button" class="w-full mb-4 px-5 py-2 bg-indigo-500 hover:bg-indigo-700 text-white cursor-pointer rounded-md" on:click=move |_| state.create_document()>
+                {"New Document"}
+            </button>
+            <ul>
+                {
+                    move || {
+                        let current = state.current_document.get();
+                        let mut documents = state.documents.get();
+                        documents.sort_by(|a, b| b.modified.total_cmp(&a.modified));
+
+                        documents.into_iter().map(|document| {
+                            let id = document.id;
+                            let name = document.name.clone();
+                            let rename_name = name.clone();
+                            let delete_name = name.clone();
+
+                            view! {
+                                <li class=format!("mb-2 p-2 rounded-md border-2 {}", if id == current { "border-indigo-500" } else { "border-gray-300 dark:border-gray-600" })>
+                                    <button type="button" class="block w-full text-left" on:click=move |_| state.open_document(id)>
+                                        <div class="font-semibold truncate">{name}</div>
+                                        <div class="flex justify-between text-xs text-gray-500 dark:text-gray-400">
+                                            <span>{format!("{} words", document.word_count())}</span>
+                                            <span>{document.modified_label()}</span>
+                                        </div>
+                                    </button>
+                                    <div class="flex justify-end space-x-1 text-gray-500 dark:text-gray-400">
+                                        <button type="button" class="p-1 hover:text-gray-900 dark:hover:text-white" title="Rename" on:click=move |_| rename(id, rename_name.clone())>
+                                            <i class="fa-solid fa-pen"></i>
+                                        </button>
+                                        <button type="button" class="p-1 hover:text-gray-900 dark:hover:text-white" title="Duplicate" on:click=move |_| state.duplicate_document(id)>
+                                            <i class="fa-solid fa-copy"></i>
+                                        </button>
+                                        <button type="button" class="p-1 hover:text-gray-900 dark:hover:text-white" title="Delete" on:click=move |_| delete(id, delete_name.clone())>
+                                            <i class="fa-solid fa-trash"></i>
+                                        </button>
+                                    </div>
+                                </li>
+                            }
+                        }).collect::<Vec<_>>()
+                    }
+                }
+            </ul>
+            <div class="text-xs text-gray-500 dark:text-gray-400">{format!("Saved in {}", storage.backend_name())}</div>
+        </div>
+    }
+}
+
 #[component]
-pub fn toggle_switch(label: &'static str, value: RwSignal<bool>) -> impl IntoView {
-    let input_ref = create_node_ref::<Input>();
-    let onchange = move |e| value.set(event_target_checked(&e));
+pub fn history_dialog(open: RwSignal<bool>) -> impl IntoView {
+    let state = use_context::<GlobalState>().unwrap_or_default();
+    // Indices into the revisions; `None` stands for the current text.
+    let from = create_rw_signal(None::<usize>);
+    let to = create_rw_signal(None::<usize>);
 
     create_effect(move |_| {
-        if let Some(inputs) = input_ref.clone().get() {
-            println!("{}", value.get());
-            inputs.set_checked(value.get());
+        if open.get() {
+            from.set(state.revisions.get_untracked().len().checked_sub(1));
+            to.set(None);
         }
     });
 
+    let version_text = move |index: Option<usize>| {
+        index
+            .and_then(|index| state.revisions.get().get(index).map(|r| r.text.clone()))
+            .unwrap_or_else(|| state.text.get())
+    };
+
+    let save_version = move |_| {
+        let count = state
+            .revisions
+            .get_untracked()
+            .iter()
+            .filter(|revision| revision.name.is_some())
+            .count();
+        let default = format!("Version {}", count + 1);
+
+        if let Ok(Some(name)) = window().prompt_with_message_and_default("Version name", &default) {
+            let name = name.trim();
+            state.save_version(Some(if name.is_empty() {
+                default
+            } else {
+                name.to_owned()
+            }));
+        }
+    };
+
+    let version_select = move |selected: RwSignal<Option<usize>>| {
+        view! {
+            <select class="bg-gray-50 border border-gray-300 text-gray-900 text-sm rounded-lg p-2 dark:bg-gray-700 dark:border-gray-600 dark:text-white" on:change=move |ev| selected.set(event_target_value(&ev).parse::<usize>().ok())>
+                <option value="current" selected=move || selected.get().is_none()>{"Current text"}</option>
+                {
+                    move || state.revisions.get().iter().enumerate().rev().map(|(index, revision)| view! {
+                        <option value=index.to_string() selected=move || selected.get() == Some(index)>{revision.label()}</option>
+                    }).collect::<Vec<_>>()
+                }
+            </select>
+        }
+    };
+
     view! {
-        <label class="relative flex justify-between items-center group p-2 text-xl">
-            {label}
-            <input _ref=input_ref type="checkbox" class="absolute left-1/2 -translate-x-1/2 w-full h-full peer appearance-none rounded-md" checked=value.get() on:change=onchange />
-            <span class="w-16 h-10 flex items-center flex-shrink-0 ml-4 p-1 bg-gray-300 rounded-full duration-300 ease-in-out peer-checked:bg-green-400 after:w-8 after:h-8 after:bg-white after:rounded-full after:shadow-md after:duration-300 peer-checked:after:translate-x-6 group-hover:after:translate-x-1"></span>
-        </label>
+        <div class=move || format!("fixed z-50 top-1/2 left-1/2 -translate-x-1/2 -translate-y-1/2 w-[48rem] max-w-full max-h-screen overflow-auto bg-white shadow-md rounded-md px-8 py-6 space-y-5 drop-shadow-lg dark:bg-slate-800 {}", if open.get() { "" } else { "hidden" })>
+            <div class="flex justify-between items-center">
+                <h1 class="text-2xl font-semibold">{"History"}</h1>
+                <button type="button" class="p-2 text-gray-500 rounded cursor-pointer hover:text-gray-900 hover:bg-gray-100 dark:text-gray-400 dark:hover:text-white dark:hover:bg-gray-600" on:click=move |_| open.set(false)>
+                    <i class="fa-solid fa-xmark"></i>
+                    <span class="sr-only">{ "Close History" }</span>
+                </button>
+            </div>
+
+            <button type="button" class="px-5 py-2 bg-indigo-500 hover:bg-indigo-700 text-white cursor-pointer rounded-md" on:click=save_version>
+                {"Save Version"}
+            </button>
+
+            <ul class="max-h-48 overflow-auto">
+                {
+                    move || {
+                        let revisions = state.revisions.get();
+                        if revisions.is_empty() {
+                            return view! { <><p>{"No versions yet. Snapshots are taken automatically every few minutes while you write."}</p></> }.into_view();
+                        }
+
+                        revisions.into_iter().rev().map(|revision| {
+                            let words = WORD_REGEX.find_iter(&state.prose(&revision.text)).count();
+                            let kind = if revision.name.is_some() { "Saved" } else { "Automatic" };
+                            let created = revision.created_label();
+                            let label = revision.label();
+
+                            view! {
+                                <li class="flex justify-between items-center px-2 py-1 border-b border-gray-300 dark:border-gray-600">
+                                    <div>
+                                        <div class="font-semibold">{label}</div>
+                                        <div class="text-xs text-gray-500 dark:text-gray-400">{format!("{kind} · {created} · {words} words")}</div>
+                                    </div>
+                                    <button type="button" class="px-3 py-1 text-sm bg-gray-200 hover:bg-gray-300 rounded-md dark:bg-gray-700 dark:hover:bg-gray-600" on:click=move |_| state.restore_revision(&revision)>
+                                        {"Restore"}
+                                    </button>
+                                </li>
+                            }
+                        }).collect::<Vec<_>>().into_view()
+                    }
+                }
+            </ul>
+
+            <div class="flex items-center space-x-2">
+                <span>{"Compare"}</span>
+                {version_select(from)}
+                <span>{"with"}</span>
+                {version_select(to)}
+            </div>
+
+            {
+                move || open.get().then(|| {
+                    let old_text = version_text(from.get());
+                    let new_text = version_text(to.get());
+                    let words_per_minute = state.reading_words_per_minute() as u32;
+                    let old = Snapshot::of(&state.prose(&old_text), words_per_minute);
+                    let new = Snapshot::of(&state.prose(&new_text), words_per_minute);
+
+                    let count_delta = |old: usize, new: usize| format!("{:+}", new as i64 - old as i64);
+                    let reading_time_delta = if new.reading_time >= old.reading_time {
+                        format!("+{}", format_duration_short(new.reading_time - old.reading_time))
+                    } else {
+                        format!("-{}", format_duration_short(old.reading_time - new.reading_time))
+                    };
+
+                    let deltas = [
+                        ("Words", count_delta(old.words, new.words)),
+                        ("Characters", count_delta(old.characters, new.characters)),
+                        ("Sentences", count_delta(old.sentences, new.sentences)),
+                        ("Paragraphs", count_delta(old.paragraphs, new.paragraphs)),
+                        ("Reading Time", reading_time_delta),
+                        ("Reading Ease", format!("{:+.1}", new.reading_ease - old.reading_ease)),
+                        ("Grade Level", format!("{:+.1}", new.grade - old.grade)),
+                    ];
+
+                    view! {
+                        <div class="flex flex-wrap">
+                            {
+                                deltas.into_iter().map(|(label, delta)| view! {
+                                    <div class="w-1/3 mb-2">
+                                        <div class="uppercase text-xs">{label}</div>
+                                        <span class="text-2xl text-black dark:text-white">{delta}</span>
+                                    </div>
+                                }).collect::<Vec<_>>()
+                            }
+                        </div>
+                        <pre class="max-h-64 overflow-auto text-sm whitespace-pre-wrap border-2 border-gray-300 rounded-md dark:border-gray-600">
+                            {
+                                history::diff_lines(&old_text, &new_text).into_iter().map(|change| match change {
+                                    Change::Unchanged(line) => view! { <div class="px-2">{format!("  {line}")}</div> },
+                                    Change::Added(line) => view! { <div class="px-2 bg-green-200 dark:bg-green-900">{format!("+ {line}")}</div> },
+                                    Change::Removed(line) => view! { <div class="px-2 bg-red-200 dark:bg-red-900">{format!("- {line}")}</div> },
+                                }).collect::<Vec<_>>()
+                            }
+                        </pre>
+                    }
+                })
+            }
+        </div>
     }
 }
 
-fn format_duration(duration: Duration) -> impl IntoView {
-    let total_seconds = duration.as_secs();
-    let minutes = total_seconds / 60;
-    let seconds = total_seconds % 60;
+/// Shows the enabled statistics of the open document next to those of a
+/// second text, such as an edit or a translation, with the words whose
+/// frequency changed.
+#[component]
+pub fn compare_dialog(open: RwSignal<bool>) -> impl IntoView {
+    let state = use_context::<GlobalState>().unwrap_or_default();
+    let other = state.with_text(state.compare_text);
 
-    if minutes > 60 {
-        let hours = minutes / 60;
-        let remaining_minutes = minutes % 60;
+    let load_document = move |ev| {
+        let id = event_target_value(&ev).parse::<u64>().ok();
+        if let Some(document) = id.and_then(|id| {
+            state
+                .documents
+                .get_untracked()
+                .into_iter()
+                .find(|document| document.id == id)
+        }) {
+            state.compare_text.set(document.text);
+        }
+    };
 
-        view! {
-            <>
-                <div class="flex flex-nowrap text-center space-x-2">
-                    <span class="text-3xl text-black dark:text-white">
-                        {hours}
-                        <div class="text-xs">{ if hours != 1 { "hrs" } else { "hr" } }</div>
-                    </span>
-                    <span class="text-3xl text-black dark:text-white">
-                        {remaining_minutes}
-                        <div class="text-xs">{ if remaining_minutes != 1 { "mins" } else { "min" } }</div>
-                    </span>
-                    <span class="text-3xl text-black dark:text-white">
-                        {seconds}
-                        <div class="text-xs">{  if seconds != 1 { "secs" } else { "sec" } }</div>
-                    </span>
+    view! {
+        <div class=move || format!("fixed z-50 top-1/2 left-1/2 -translate-x-1/2 -translate-y-1/2 w-[64rem] max-w-full max-h-screen overflow-auto bg-white shadow-md rounded-md px-8 py-6 space-y-5 drop-shadow-lg dark:bg-slate-800 {}", if open.get() { "" } else { "hidden" })>
+            <div class="flex justify-between items-center">
+                <h1 class="text-2xl font-semibold">{"Compare"}</h1>
+                <button type="button" class="p-2 text-gray-500 rounded cursor-pointer hover:text-gray-900 hover:bg-gray-100 dark:text-gray-400 dark:hover:text-white dark:hover:bg-gray-600" on:click=move |_| open.set(false)>
+                    <i class="fa-solid fa-xmark"></i>
+                    <span class="sr-only">{ "Close Compare" }</span>
+                </button>
+            </div>
+
+            <div class="flex space-x-4">
+                <div class="w-1/2">
+                    <div class="uppercase text-xs mb-1 h-9 flex items-end">{"Current document"}</div>
+                    <textarea class="w-full h-40 p-2 text-sm border-2 border-gray-300 rounded-md bg-gray-100 dark:bg-gray-700 dark:border-gray-600" readonly prop:value=move || state.text.get()></textarea>
                 </div>
-            </>
-        }
-    } else if minutes > 0 {
-        view! {
-            <>
-                <div class="flex flex-nowrap text-center space-x-2">
-                    <span class="text-3xl text-black dark:text-white">
-                        {minutes}
-                        <div class="text-xs">{ if minutes != 1 { "mins" } else { "min" } }</div>
-                    </span>
-                    <span class="text-3xl text-black dark:text-white">
-                        {seconds}
-                        <div class="text-xs">{ if seconds != 1 { "secs" } else { "sec" } }</div>
-                    </span>
+                <div class="w-1/2">
+                    <div class="flex justify-between items-end mb-1">
+                        <span class="uppercase text-xs">{"Compared text"}</span>
+                        <select class="bg-gray-50 border border-gray-300 text-gray-900 text-sm rounded-lg p-2 dark:bg-gray-700 dark:border-gray-600 dark:text-white" on:change=load_document>
+                            <option value="" selected>{"Load a document…"}</option>
+                            {
+                                move || state.documents.get().into_iter()
+                                    .filter(|document| document.id != state.current_document.get())
+                                    .map(|document| view! { <option value=document.id.to_string()>{document.name}</option> })
+                                    .collect::<Vec<_>>()
+                            }
+                        </select>
+                    </div>
+                    <textarea class="w-full h-40 p-2 text-sm border-2 border-gray-300 rounded-md dark:bg-gray-700 dark:border-gray-600" placeholder="Paste the text to compare with" prop:value=move || state.compare_text.get() on:input=move |ev| state.compare_text.set(event_target_value(&ev))></textarea>
                 </div>
-            </>
+            </div>
+
+            {
+                move || open.get().then(|| view! {
+                    <table class="w-full text-sm">
+                        <thead>
+                            <tr class="uppercase text-xs text-left border-b-2 border-gray-700">
+                                <th class="py-1">{"Statistic"}</th>
+                                <th class="py-1 text-right">{"Current"}</th>
+                                <th class="py-1 text-right">{"Compared"}</th>
+                                <th class="py-1 text-right">{"Change"}</th>
+                            </tr>
+                        </thead>
+                        <tbody>
+                            {
+                                state.statistics_options.get().into_iter().filter(|option| option.is_comparable()).map(|option| {
+                                    let current = state.statistic(option);
+                                    let compared = other.statistic(option);
+
+                                    view! {
+                                        <tr class="border-b border-gray-300 dark:border-gray-600">
+                                            <td class="py-1">{option.label()}</td>
+                                            <td class="py-1 text-right">{format_statistic(option, current)}</td>
+                                            <td class="py-1 text-right">{format_statistic(option, compared)}</td>
+                                            <td class="py-1 text-right font-semibold text-black dark:text-white">{format_statistic_delta(option, compared - current)}</td>
+                                        </tr>
+                                    }
+                                }).collect::<Vec<_>>()
+                            }
+                        </tbody>
+                    </table>
+                })
+            }
+
+            {
+                move || open.get().then(|| {
+                    let match_case = state.match_case.get();
+                    let before = compare::word_counts(&state.content(), match_case);
+                    let after = compare::word_counts(&other.content(), match_case);
+                    let overlap = compare::vocabulary_overlap(&before, &after);
+                    let changes = compare::keyword_changes(&before, &after);
+
+                    let change_list = move |title: &'static str, increased: bool| {
+                        let items = changes
+                            .iter()
+                            .filter(|change| (change.delta() > 0) == increased)
+                            .take(KEYWORD_CHANGES_SHOWN)
+                            .map(|change| view! {
+                                <li class="flex justify-between px-2 border-b border-gray-300 dark:border-gray-600">
+                                    <span class="overflow-hidden overflow-ellipsis">{change.word.clone()}</span>
+                                    <span class="whitespace-nowrap">{format!("{} → {} ({:+})", change.before, change.after, change.delta())}</span>
+                                </li>
+                            })
+                            .collect::<Vec<_>>();
+
+                        view! {
+                            <div class="w-1/2">
+                                <div class="uppercase text-xs mb-1">{title}</div>
+                                <ul class="max-h-48 overflow-auto text-sm">{items}</ul>
+                            </div>
+                        }
+                    };
+
+                    view! {
+                        <div>
+                            <div class="uppercase text-xs">{"Vocabulary Overlap (Jaccard)"}</div>
+                            <span class="text-4xl text-black dark:text-white">
+                                {overlap.map_or_else(|| String::from("—"), |overlap| format!("{:.0}%", overlap * 100.0))}
+                            </span>
+                            <div class="text-xs">{"Share of the distinct words in either text that appear in both"}</div>
+                        </div>
+                        <div class="flex space-x-4">
+                            {change_list("Added or more frequent", true)}
+                            {change_list("Removed or less frequent", false)}
+                        </div>
+                    }
+                })
+            }
+        </div>
+    }
+}
+
+/// Compares a source text with its translation segment by segment, from
+/// pasted lines or an imported TMX or XLIFF file, and flags segments that
+/// grew more than the document allows.
+#[component]
+pub fn translation_dialog(open: RwSignal<bool>) -> impl IntoView {
+    let state = use_context::<GlobalState>().unwrap_or_default();
+    // The pasted texts as typed, so that blank lines survive editing.
+    let source_text = create_rw_signal(String::new());
+    let target_text = create_rw_signal(String::new());
+    let import_error = create_rw_signal(None::<String>);
+    let file_input = create_node_ref::<Input>();
+
+    let show_translation = move || {
+        let translation = state.translation.get_untracked();
+        source_text.set(translation.lines(false));
+        target_text.set(translation.lines(true));
+    };
+
+    create_effect(move |_| {
+        if open.get() {
+            import_error.set(None);
+            show_translation();
         }
-    } else {
-        view! {
-            <>
-                <div class="flex flex-nowrap text-center">
-                    <span class="text-3xl text-black dark:text-white">
-                        {seconds}
-                        <div class="text-xs">{"secs"}</div>
-                    </span>
+    });
+
+    let update_segments = move || {
+        state.translation.update(|translation| {
+            translation.segments =
+                Translation::pair(&source_text.get_untracked(), &target_text.get_untracked());
+        });
+    };
+
+    let import = move |_| {
+        let Some(file) = file_input
+            .get()
+            .and_then(|input| input.files())
+            .and_then(|files| files.get(0))
+        else {
+            return;
+        };
+
+        let name = file.name();
+        spawn_local(async move {
+            let Ok(contents) = read_as_text(&File::from(file)).await else {
+                return;
+            };
+
+            match Translation::import(&name, &contents) {
+                Ok(translation) => {
+                    import_error.set(None);
+                    state.translation.set(translation);
+                    show_translation();
+                }
+                Err(error) => {
+                    import_error.set(Some(format!("Could not import the file: {error}.")))
+                }
+            }
+        });
+    };
+
+    let language = move |target: bool| {
+        let translation = state.translation.get();
+        let language = if target {
+            translation.target_language
+        } else {
+            translation.source_language
+        };
+        match (target, language.is_empty()) {
+            (false, true) => String::from("Source"),
+            (true, true) => String::from("Translation"),
+            (false, false) => format!("Source ({language})"),
+            (true, false) => format!("Translation ({language})"),
+        }
+    };
+
+    view! {
+        <div class=move || format!("fixed z-50 top-1/2 left-1/2 -translate-x-1/2 -translate-y-1/2 w-[64rem] max-w-full max-h-screen overflow-auto bg-white shadow-md rounded-md px-8 py-6 space-y-5 drop-shadow-lg dark:bg-slate-800 {}", if open.get() { "" } else { "hidden" })>
+            <div class="flex justify-between items-center">
+                <h1 class="text-2xl font-semibold">{"Translation"}</h1>
+                <button type="button" class="p-2 text-gray-500 rounded cursor-pointer hover:text-gray-900 hover:bg-gray-100 dark:text-gray-400 dark:hover:text-white dark:hover:bg-gray-600" on:click=move |_| open.set(false)>
+                    <i class="fa-solid fa-xmark"></i>
+                    <span class="sr-only">{ "Close Translation" }</span>
+                </button>
+            </div>
+
+            <div class="flex justify-between items-center">
+                <div class="flex items-center space-x-2">
+                    <button type="button" class="px-5 py-2 bg-indigo-500 hover:bg-indigo-700 text-white cursor-pointer rounded-md" on:click=move |_| if let Some(input) = file_input.get() { input.click() }>
+                        {"Import TMX, XLIFF or PO"}
+                    </button>
+                    <input _ref=file_input type="file" class="hidden" accept=".tmx,.xlf,.xliff,.po,.pot" on:change=import />
                 </div>
-            </>
+                <label class="flex items-center space-x-2">
+                    <span class="text-sm">{"Max. Length (% of Source)"}</span>
+                    <input type="number" min="100" step="5" class="w-24 bg-gray-50 border border-gray-300 text-gray-900 text-sm rounded-lg p-2 dark:bg-gray-700 dark:border-gray-600 dark:text-white"
+                        prop:value=move || (state.max_expansion.get() * 100.0).round().to_string()
+                        on:change=move |ev| {
+                            if let Ok(percent) = event_target_value(&ev).parse::<f64>() {
+                                if percent > 0.0 {
+                                    state.max_expansion.set(percent / 100.0);
+                                }
+                            }
+                        } />
+                </label>
+            </div>
+            {move || import_error.get().map(|error| view! { <p class="text-red-600 dark:text-red-400">{error}</p> })}
+
+            <div class="flex space-x-4">
+                <div class="w-1/2">
+                    <div class="uppercase text-xs mb-1">{move || language(false)}</div>
+                    <textarea class="w-full h-40 p-2 text-sm border-2 border-gray-300 rounded-md dark:bg-gray-700 dark:border-gray-600" placeholder="One segment per line"
+                        prop:value=move || source_text.get()
+                        on:input=move |ev| { source_text.set(event_target_value(&ev)); update_segments(); }></textarea>
+                </div>
+                <div class="w-1/2">
+                    <div class="uppercase text-xs mb-1">{move || language(true)}</div>
+                    <textarea class="w-full h-40 p-2 text-sm border-2 border-gray-300 rounded-md dark:bg-gray-700 dark:border-gray-600" placeholder="The translation of each line"
+                        prop:value=move || target_text.get()
+                        on:input=move |ev| { target_text.set(event_target_value(&ev)); update_segments(); }></textarea>
+                </div>
+            </div>
+
+            {
+                move || open.get().then(|| {
+                    let translation = state.translation.get();
+                    let unit = state.character_unit.get();
+                    let max_expansion = state.max_expansion.get();
+                    let source = translation.source_totals(unit);
+                    let target = translation.target_totals(unit);
+                    let exceeding = translation.segments.iter().filter(|segment| segment.exceeds(unit, max_expansion)).count();
+                    let percent = |ratio: Option<f64>| ratio.map_or_else(|| String::from("—"), |ratio| format!("{:.0}%", ratio * 100.0));
+
+                    let tiles = [
+                        ("Source Words", source.words.to_string()),
+                        ("Translation Words", target.words.to_string()),
+                        ("Source Characters", source.characters.to_string()),
+                        ("Translation Characters", target.characters.to_string()),
+                        ("Length of Source", percent(translation.expansion(unit))),
+                        ("Segments Over Limit", format!("{exceeding} / {}", translation.segments.len())),
+                    ];
+
+                    view! {
+                        <div class="flex flex-wrap">
+                            {
+                                tiles.into_iter().map(|(label, value)| view! {
+                                    <div class="w-1/3 mb-2">
+                                        <div class="uppercase text-xs">{label}</div>
+                                        <span class="text-2xl text-black dark:text-white">{value}</span>
+                                    </div>
+                                }).collect::<Vec<_>>()
+                            }
+                        </div>
+                        <div class="max-h-72 overflow-auto">
+                            <table class="w-full text-sm">
+                                <thead>
+                                    <tr class="uppercase text-xs text-left border-b-2 border-gray-700">
+                                        <th class="py-1 pr-2">{"ID"}</th>
+                                        <th class="py-1 pr-2">{"Source"}</th>
+                                        <th class="py-1 pr-2">{"Translation"}</th>
+                                        <th class="py-1 pr-2 text-right">{"Characters"}</th>
+                                        <th class="py-1 text-right">{"Length"}</th>
+                                    </tr>
+                                </thead>
+                                <tbody>
+                                    {
+                                        translation.segments.into_iter().map(|segment| {
+                                            let row = if segment.exceeds(unit, max_expansion) { "bg-red-200 dark:bg-red-900" } else { "" };
+                                            let characters = format!("{} → {}", unit.count(&segment.source), unit.count(&segment.target));
+
+                                            view! {
+                                                <tr class=format!("align-top border-b border-gray-300 dark:border-gray-600 {row}")>
+                                                    <td class="py-1 pr-2">{segment.id.clone()}</td>
+                                                    <td class="py-1 pr-2">{segment.source.clone()}</td>
+                                                    <td class="py-1 pr-2">{segment.target.clone()}</td>
+                                                    <td class="py-1 pr-2 text-right whitespace-nowrap">{characters}</td>
+                                                    <td class="py-1 text-right">{percent(segment.expansion(unit))}</td>
+                                                </tr>
+                                            }
+                                        }).collect::<Vec<_>>()
+                                    }
+                                </tbody>
+                            </table>
+                        </div>
+                    }
+                })
+            }
+        </div>
+    }
+}
+
+/// How many words the compare dialog lists in each direction.
+const KEYWORD_CHANGES_SHOWN: usize = 25;
+
+/// Formats a value from [`GlobalState::statistic`] the way its tile does.
+fn format_statistic(option: StatisticOption, value: f64) -> String {
+    match option {
+        StatisticOption::ReadingTime
+        | StatisticOption::SpeakingTime
+        | StatisticOption::HandWritingTime => format_duration_short(Duration::from_secs_f64(value)),
+        StatisticOption::AvgSentenceWords
+        | StatisticOption::AvgSentenceChars
+        | StatisticOption::AvgWordLength
+        | StatisticOption::SubtitleCharactersPerSecond => format!("{value:.1}"),
+        _ => format!("{value:.0}"),
+    }
+}
+
+fn format_statistic_delta(option: StatisticOption, delta: f64) -> String {
+    match option {
+        StatisticOption::ReadingTime
+        | StatisticOption::SpeakingTime
+        | StatisticOption::HandWritingTime => {
+            let sign = if delta < 0.0 { "-" } else { "+" };
+            format!("{sign}{}", format_statistic(option, delta.abs()))
         }
+        StatisticOption::AvgSentenceWords
+        | StatisticOption::AvgSentenceChars
+        | StatisticOption::AvgWordLength
+        | StatisticOption::SubtitleCharactersPerSecond => format!("{delta:+.1}"),
+        _ => format!("{delta:+.0}"),
+    }
+}
+
+fn platform_gauge(platform: Platform, text: &str) -> View {
+    let measurement = platform.measure(text);
+    let remaining = measurement.remaining();
+    let over = remaining < 0;
+    let progress = (measurement.used as f64 / measurement.limit.max(1) as f64).min(1.0);
+
+    view! {
+        <div class="border-b-2 border-gray-700 mb-4 pb-4">
+            <div class="flex justify-between items-baseline">
+                <div class="uppercase text-xs">{platform.label()}</div>
+                <span class="text-black dark:text-white">{format!("{} / {} {}", measurement.used, measurement.limit, measurement.unit)}</span>
+            </div>
+            <div class="w-full h-2 my-2 bg-gray-300 rounded-full dark:bg-gray-700">
+                <div class=format!("h-2 rounded-full {}", if over { "bg-red-500" } else if progress > 0.9 { "bg-yellow-400" } else { "bg-indigo-500" }) style=format!("width: {:.1}%", progress * 100.0)></div>
+            </div>
+            <div class=if over { "text-sm font-semibold text-red-600 dark:text-red-400" } else { "text-sm" }>
+                {
+                    if over {
+                        format!("{} {} over the limit", -remaining, measurement.unit)
+                    } else {
+                        format!("{remaining} {} remaining", measurement.unit)
+                    }
+                }
+            </div>
+            {measurement.note.map(|note| view! { <div class="text-xs">{note}</div> })}
+        </div>
     }
+    .into_view()
+}
+
+fn goal_progress(state: GlobalState, goal: Goal) -> View {
+    let value = state.goal_value(goal.metric);
+    let status = goal.status(value);
+    let unit = goal.metric.unit();
+    let amount = |value: f64| match goal.metric {
+        GoalMetric::ReadingTime => format!("{value:.1}"),
+        _ => format!("{value:.0}"),
+    };
+
+    let range = match goal.upper_bound() {
+        Some(maximum) => format!("{}–{maximum}", goal.target),
+        None => goal.target.to_string(),
+    };
+
+    let message = match status {
+        GoalStatus::InProgress => format!("{} {unit} to go", amount(goal.target as f64 - value)),
+        GoalStatus::Reached if goal.upper_bound().is_some() => String::from("Within range"),
+        GoalStatus::Reached => String::from("Goal reached"),
+        GoalStatus::OverLimit => format!(
+            "Over the limit by {} {unit}",
+            amount(value - goal.upper_bound().unwrap_or_default() as f64)
+        ),
+    };
+
+    let deadline = goal.days_left().map(|days| match days {
+        ..=0 => String::from("Deadline passed"),
+        _ if status == GoalStatus::InProgress => format!(
+            "Due in {days} {} · {} {unit} per day",
+            if days == 1 { "day" } else { "days" },
+            amount(((goal.target as f64 - value) / days as f64).ceil())
+        ),
+        _ => format!("Due in {days} {}", if days == 1 { "day" } else { "days" }),
+    });
+
+    let bar = match status {
+        GoalStatus::InProgress => "bg-indigo-500",
+        GoalStatus::Reached => "bg-green-500",
+        GoalStatus::OverLimit => "bg-red-500",
+    };
+
+    let minimum_marker = goal.upper_bound().map(|maximum| {
+        let position = (goal.target as f64 / maximum as f64 * 100.0).min(100.0);
+        view! {
+            <div class="absolute top-0 h-2 w-0.5 bg-gray-900 dark:bg-white" style=format!("left: {position:.1}%")></div>
+        }
+    });
+
+    view! {
+        <div class="border-b-2 border-gray-700 mb-4 pb-4">
+            <div class="flex justify-between items-baseline">
+                <div class="uppercase text-xs">{format!("Goal ({})", goal.metric.label())}</div>
+                <span class="text-black dark:text-white">{format!("{} / {range} {unit}", amount(value))}</span>
+            </div>
+            <div class="relative w-full h-2 my-2 bg-gray-300 rounded-full dark:bg-gray-700">
+                <div class=format!("h-2 rounded-full {bar}") style=format!("width: {:.1}%", goal.progress(value) * 100.0)></div>
+                {minimum_marker}
+            </div>
+            <div class=if status == GoalStatus::OverLimit { "text-sm font-semibold text-red-600 dark:text-red-400" } else { "text-sm" } role=if status == GoalStatus::OverLimit { "alert" } else { "status" }>
+                {(status == GoalStatus::OverLimit).then(|| view! { <i class="fa-solid fa-triangle-exclamation mr-1"></i> })}
+                {message}
+            </div>
+            {deadline.map(|deadline| view! { <div class="text-xs">{deadline}</div> })}
+        </div>
+    }
+    .into_view()
+}
+
+/// Weeks of activity shown in the heatmap.
+const HEATMAP_WEEKS: usize = 18;
+
+#[component]
+pub fn writing_activity_panel() -> impl IntoView {
+    let state = use_context::<GlobalState>().unwrap_or_default();
+
+    move || {
+        let log = state.writing_log.get();
+        let now = Date::now();
+        let today = sessions::date(now);
+
+        // Start on the Sunday that makes today the last cell of the grid.
+        let mut day = sessions::date(now);
+        for _ in 0..(HEATMAP_WEEKS - 1) * 7 + today.get_day() as usize {
+            day = sessions::previous_day(&day);
+        }
+
+        let mut days = Vec::new();
+        while days.len() < HEATMAP_WEEKS * 7 {
+            days.push((day.get_time() <= now).then(|| sessions::day_key(&day)));
+            day = sessions::next_day(&day);
+        }
+
+        let busiest = days
+            .iter()
+            .flatten()
+            .map(|key| log.words_on(key))
+            .max()
+            .unwrap_or_default()
+            .max(1);
+
+        let words_per_hour = log.active_session(now).map_or_else(
+            || String::from("–"),
+            |session| format!("{:.0}", session.words_per_hour()),
+        );
+
+        view! {
+            <div class="mb-4 bg-white p-3 rounded-md border-2 border-gray-700 dark:bg-gray-800">
+                <div class="text-3xl mt-2 mb-4 h5">{"Writing Activity"}</div>
+                <div class="border-b-2 border-gray-700 flex justify-between mb-4">
+                    <div class="w-2/5">
+                        <div class="uppercase text-xs">{"Today"}</div>
+                        <span class="text-4xl text-black dark:text-white">{log.words_on(&sessions::day_key(&today))}</span>
+                    </div>
+                    <div class="w-2/5">
+                        <div class="uppercase text-xs">{"Words per Hour"}</div>
+                        <span class="text-4xl text-black dark:text-white">{words_per_hour}</span>
+                    </div>
+                </div>
+                <div class="border-b-2 border-gray-700 flex justify-between mb-4">
+                    <div class="w-2/5">
+                        <div class="uppercase text-xs">{"Current Streak"}</div>
+                        <span class="text-4xl text-black dark:text-white">{format!("{}d", log.current_streak(now))}</span>
+                    </div>
+                    <div class="w-2/5">
+                        <div class="uppercase text-xs">{"Longest Streak"}</div>
+                        <span class="text-4xl text-black dark:text-white">{format!("{}d", log.longest_streak())}</span>
+                    </div>
+                </div>
+                <div class="grid grid-rows-7 grid-flow-col gap-0.5 w-max">
+                    {
+                        days.into_iter().map(|key| match key {
+                            Some(key) => {
+                                let words = log.words_on(&key);
+                                let shade = match words * 4 / busiest {
+                                    _ if words <= 0 => "bg-gray-200 dark:bg-gray-700",
+                                    0 => "bg-green-200",
+                                    1 => "bg-green-400",
+                                    2 => "bg-green-600",
+                                    _ => "bg-green-800",
+                                };
+
+                                view! { <div class=format!("w-3 h-3 rounded-sm {shade}") title=format!("{key}: {words} words")></div> }
+                            }
+                            None => view! { <div class="w-3 h-3"></div> },
+                        }).collect::<Vec<_>>()
+                    }
+                </div>
+            </div>
+        }
+    }
+}
+
+#[component]
+pub fn character_frequency_panel() -> impl IntoView {
+    let state = use_context::<GlobalState>().unwrap_or_default();
+    let fold_case = create_rw_signal(true);
+    let letters_only = create_rw_signal(true);
+
+    let export = move |_: MouseEvent| {
+        let frequencies = frequency::frequencies(
+            &state.content(),
+            fold_case.get_untracked(),
+            letters_only.get_untracked(),
+        );
+
+        download_csv(
+            "characters.csv",
+            &["Character", "Count", "Percent", "English Percent"],
+            frequencies.into_iter().map(|frequency| {
+                let expected = frequency.expected_english();
+                (
+                    frequency.grapheme,
+                    frequency.count,
+                    frequency.percent,
+                    expected,
+                )
+            }),
+        );
+    };
+
+    view! {
+        <div class="mt-4 bg-white p-3 rounded-md border-2 border-gray-700 dark:bg-gray-800">
+            <div class="flex justify-between items-center">
+                <div class="text-3xl mt-2 mb-4 h5">{"Character Frequency"}</div>
+                <button type="button" class="p-2 text-gray-500 rounded cursor-pointer hover:text-gray-900 hover:bg-gray-100 dark:text-gray-400 dark:hover:text-white dark:hover:bg-gray-600" on:click=export>
+                    <img class="w-4 h-4" src="public/icons/download.svg" alt="Export CSV" height="48" width="48" />
+                    <span class="sr-only">{ "Export Character Frequency CSV" }</span>
+                </button>
+            </div>
+            <ToggleSwitch label="Case Fold" value=fold_case/>
+            <ToggleSwitch label="Letters Only" value=letters_only/>
+            {
+                move || {
+                    let frequencies = frequency::frequencies(&state.content(), fold_case.get(), letters_only.get());
+                    if frequencies.is_empty() {
+                        return view! { <p>{"Start typing to see how often each character is used"}</p> }.into_view();
+                    }
+
+                    let entropy = frequency::entropy(&frequencies);
+                    let chi_squared = frequency::english_chi_squared(&frequencies)
+                        .map_or_else(|| String::from("–"), |chi_squared| format!("{chi_squared:.1}"));
+                    let highest = frequencies[0].percent.max(ENGLISH_MAXIMUM);
+
+                    view! {
+                        <div class="border-b-2 border-gray-700 flex justify-between my-4">
+                            <div class="w-2/5">
+                                <div class="uppercase text-xs">{"Entropy (Bits/Char)"}</div>
+                                <span class="text-4xl text-black dark:text-white">{format!("{entropy:.2}")}</span>
+                            </div>
+                            <div class="w-2/5">
+                                <div class="uppercase text-xs" title="Chi-squared distance of the letters from English; lower is closer">{"English χ²"}</div>
+                                <span class="text-4xl text-black dark:text-white">{chi_squared}</span>
+                            </div>
+                        </div>
+                        <div class="relative overflow-auto max-h-56">
+                            <table class="w-full text-sm">
+                                <thead>
+                                    <tr class="text-left text-xs uppercase">
+                                        <th class="px-2">{"Character"}</th>
+                                        <th class="px-2 text-right">{"Count"}</th>
+                                        <th class="px-2 w-1/2">{"Share vs English"}</th>
+                                    </tr>
+                                </thead>
+                                <tbody>
+                                    {
+                                        frequencies.into_iter().enumerate().map(|(index, frequency)| {
+                                            let expected = frequency.expected_english();
+                                            let title = match expected {
+                                                Some(expected) => format!("{:.2}% here, {expected:.2}% in English", frequency.percent),
+                                                None => format!("{:.2}%", frequency.percent),
+                                            };
+
+                                            view! {
+                                                <tr class=if index % 2 == 0 { "bg-gray-300 dark:bg-gray-800" } else { "bg-white dark:bg-gray-800" } title=title>
+                                                    <td class="px-2 font-mono">{frequency::display(&frequency.grapheme)}</td>
+                                                    <td class="px-2 text-right font-semibold">{frequency.count}</td>
+                                                    <td class="px-2">
+                                                        <div class="h-2 bg-indigo-500 rounded-full" style=format!("width: {:.1}%", frequency.percent / highest * 100.0)></div>
+                                                        {expected.map(|expected| view! {
+                                                            <div class="h-1 mt-0.5 bg-gray-500 rounded-full" style=format!("width: {:.1}%", expected / highest * 100.0)></div>
+                                                        })}
+                                                    </td>
+                                                </tr>
+                                            }
+                                        }).collect::<Vec<_>>()
+                                    }
+                                </tbody>
+                            </table>
+                        </div>
+                    }
+                    .into_view()
+                }
+            }
+        </div>
+    }
+}
+
+fn reading_estimate_breakdown(estimate: &ReadingEstimate) -> String {
+    let signed = |seconds: f64| {
+        let duration = format_duration_short(Duration::from_secs_f64(seconds.abs()));
+        if seconds < 0.0 {
+            format!("-{duration}")
+        } else {
+            format!("+{duration}")
+        }
+    };
+
+    let mut lines = vec![
+        format!(
+            "Flat estimate: {}",
+            format_duration_short(Duration::from_secs_f64(estimate.base))
+        ),
+        format!(
+            "Difficulty (reading ease {:.0}): {}",
+            estimate.reading_ease,
+            signed(estimate.difficulty)
+        ),
+    ];
+
+    for (label, count, seconds) in [
+        ("Images", estimate.image_count, estimate.images),
+        ("Code lines", estimate.code_line_count, estimate.code),
+        ("Numbers", estimate.number_count, estimate.numbers),
+        ("Table rows", estimate.table_row_count, estimate.tables),
+    ] {
+        if count > 0 {
+            lines.push(format!("{label} ({count}): {}", signed(seconds)));
+        }
+    }
+
+    lines.join("\n")
+}
+
+fn speed_profile_summary(profile: &SpeedProfile, activity: Activity) -> String {
+    let mut summary = format!(
+        "Based on {}: {} {}",
+        profile.name,
+        profile.rate,
+        activity.unit()
+    );
+
+    if profile.sentence_pause > 0.0 {
+        summary += &format!(", {}s pause per sentence", profile.sentence_pause);
+    }
+
+    if profile.paragraph_pause > 0.0 {
+        summary += &format!(", {}s pause per paragraph", profile.paragraph_pause);
+    }
+
+    summary
+}
+
+#[component]
+pub fn speed_profile_editor(activity: Activity) -> impl IntoView {
+    let state = use_context::<GlobalState>().unwrap_or_default();
+    let profile = move || state.speed_profiles.get().get(activity).clone();
+    let update = move |change: &dyn Fn(&mut SpeedProfile)| {
+        state
+            .speed_profiles
+            .update(|profiles| change(profiles.get_mut(activity)));
+    };
+
+    let select_preset = move |ev| {
+        let value: String = event_target_value(&ev);
+        if let Some(preset) = value
+            .parse::<usize>()
+            .ok()
+            .and_then(|index| activity.presets().into_iter().nth(index))
+        {
+            update(&|profile| *profile = preset.clone());
+        }
+    };
+
+    let input_class = "bg-gray-50 border border-gray-300 text-gray-900 text-sm rounded-lg focus:ring-blue-500 focus:border-blue-500 block w-full p-2.5 dark:bg-gray-700 dark:border-gray-600 dark:placeholder-gray-400 dark:text-white dark:focus:ring-blue-500 dark:focus:border-blue-500";
+
+    view! {
+        <label class="block p-2">
+            {format!("{} Profile", activity.label())}
+            <select class=input_class on:change=select_preset>
+                {
+                    move || {
+                        let current = profile();
+                        let presets = activity.presets();
+                        let edited = (!presets.contains(&current)).then(|| view! {
+                            <option value="" selected disabled>{format!("{} ({})", current.name, current.rate)}</option>
+                        });
+
+                        view! {
+                            {edited}
+                            {
+                                presets.into_iter().enumerate().map(|(index, preset)| view! {
+                                    <option value=index.to_string() selected=preset == current>{format!("{} ({})", preset.name, preset.rate)}</option>
+                                }).collect::<Vec<_>>()
+                            }
+                        }
+                    }
+                }
+            </select>
+        </label>
+        <label class="block p-2">
+            {"Name"}
+            <input type="text" class=input_class prop:value=move || profile().name on:input=move |ev| { let name = event_target_value(&ev); update(&|profile| profile.name = name.clone()) } />
+        </label>
+        <label class="block p-2">
+            {format!("Speed ({})", activity.unit())}
+            <input type="number" min="1" class=input_class prop:value=move || profile().rate on:input=move |ev| {
+                if let Some(rate) = event_target_value(&ev).parse::<u32>().ok().filter(|rate| *rate > 0) {
+                    update(&|profile| profile.rate = rate);
+                }
+            } />
+        </label>
+        <label class="block p-2">
+            {"Pause per Sentence (seconds)"}
+            <input type="number" min="0" max=MAX_PAUSE step="0.1" class=input_class prop:value=move || profile().sentence_pause on:input=move |ev| {
+                if let Some(pause) = event_target_value(&ev).parse::<f64>().ok().filter(|pause| (0.0..=MAX_PAUSE).contains(pause)) {
+                    update(&|profile| profile.sentence_pause = pause);
+                }
+            } />
+        </label>
+        <label class="block p-2">
+            {"Pause per Paragraph (seconds)"}
+            <input type="number" min="0" max=MAX_PAUSE step="0.1" class=input_class prop:value=move || profile().paragraph_pause on:input=move |ev| {
+                if let Some(pause) = event_target_value(&ev).parse::<f64>().ok().filter(|pause| (0.0..=MAX_PAUSE).contains(pause)) {
+                    update(&|profile| profile.paragraph_pause = pause);
+                }
+            } />
+        </label>
+    }
+}
+
+#[component]
+pub fn undo_toast() -> impl IntoView {
+    let state = use_context::<GlobalState>().unwrap_or_default();
+
+    create_effect(move |_| {
+        if let Some(created) = state.undo_toast.get() {
+            Timeout::new(8_000, move || {
+                if state.undo_toast.get_untracked() == Some(created) {
+                    state.undo_toast.set(None);
+                }
+            })
+            .forget();
+        }
+    });
+
+    view! {
+        {
+            move || state.undo_toast.get().and_then(|_| state.undo_stack.get().last().cloned()).map(|entry| view! {
+                <div class="fixed z-50 bottom-4 left-1/2 -translate-x-1/2 flex items-center space-x-4 px-4 py-2 bg-gray-800 text-white rounded-md shadow-md dark:bg-gray-200 dark:text-gray-900" role="status">
+                    <span>{entry.label}</span>
+                    <button type="button" class="font-semibold text-indigo-300 hover:text-indigo-100 dark:text-indigo-700 dark:hover:text-indigo-900" on:click=move |_| state.undo()>
+                        {"Undo"}
+                    </button>
+                    <button type="button" class="p-1 rounded cursor-pointer hover:bg-gray-700 dark:hover:bg-gray-300" on:click=move |_| state.undo_toast.set(None)>
+                        <i class="fa-solid fa-xmark"></i>
+                        <span class="sr-only">{ "Dismiss" }</span>
+                    </button>
+                </div>
+            })
+        }
+    }
+}
+
+/// How often the teleprompter advances while playing.
+const TELEPROMPTER_TICK_MILLISECONDS: u32 = 50;
+const MIN_TELEPROMPTER_SPEED: f64 = 0.5;
+const MAX_TELEPROMPTER_SPEED: f64 = 2.0;
+
+/// Scrolls the text full screen so that it reaches the end when the
+/// speaking time estimate runs out, sped up or slowed down by `speed`.
+#[component]
+pub fn teleprompter(open: RwSignal<bool>) -> impl IntoView {
+    let state = use_context::<GlobalState>().unwrap_or_default();
+    let playing = create_rw_signal(false);
+    let speed = create_rw_signal(1.0_f64);
+    let mirrored = create_rw_signal(false);
+    // How far into the estimate the text has scrolled, and how long it has
+    // actually been playing.
+    let position = create_rw_signal(Duration::ZERO);
+    let elapsed = create_rw_signal(Duration::ZERO);
+    let scroller = create_node_ref::<Div>();
+
+    let estimated = create_memo(move |_| {
+        if open.get() {
+            state.activity_time(Activity::Speaking)
+        } else {
+            Duration::ZERO
+        }
+    });
+
+    let restart = move || {
+        playing.set(false);
+        position.set(Duration::ZERO);
+        elapsed.set(Duration::ZERO);
+    };
+
+    let change_speed = move |change: f64| {
+        speed.update(|speed| {
+            *speed = ((*speed + change) * 10.0).round() / 10.0;
+            *speed = speed.clamp(MIN_TELEPROMPTER_SPEED, MAX_TELEPROMPTER_SPEED);
+        });
+    };
+
+    let seek = move |seconds: f64| {
+        position.update(|position| {
+            let seconds = (position.as_secs_f64() + seconds)
+                .clamp(0.0, estimated.get_untracked().as_secs_f64());
+            *position = Duration::from_secs_f64(seconds);
+        });
+    };
+
+    create_effect(move |_| {
+        if open.get() {
+            restart();
+        }
+    });
+
+    // Only ticks while playing, so that a closed or paused teleprompter
+    // leaves no timer running.
+    let ticker = Rc::new(RefCell::new(None::<Interval>));
+    create_effect({
+        let ticker = Rc::clone(&ticker);
+        move |_| {
+            let active = open.get() && playing.get();
+            *ticker.borrow_mut() = active.then(|| {
+                Interval::new(TELEPROMPTER_TICK_MILLISECONDS, move || {
+                    let tick = Duration::from_millis(TELEPROMPTER_TICK_MILLISECONDS.into());
+                    elapsed.update(|elapsed| *elapsed += tick);
+                    position.update(|position| *position += tick.mul_f64(speed.get_untracked()));
+
+                    if position.get_untracked() >= estimated.get_untracked() {
+                        playing.set(false);
+                    }
+                })
+            });
+        }
+    });
+    on_cleanup(move || drop(ticker.take()));
+
+    create_effect(move |_| {
+        let estimated = estimated.get().as_secs_f64();
+        let progress = if estimated == 0.0 {
+            0.0
+        } else {
+            (position.get().as_secs_f64() / estimated).min(1.0)
+        };
+
+        if let Some(scroller) = scroller.get() {
+            let range = scroller.scroll_height() - scroller.client_height();
+            scroller.set_scroll_top((f64::from(range) * progress) as i32);
+        }
+    });
+
+    let handle = window_event_listener(ev::keydown, move |ev| {
+        if !open.get_untracked() {
+            return;
+        }
+
+        match ev.key().as_str() {
+            " " | "k" => playing.update(|playing| *playing = !*playing),
+            "ArrowUp" => change_speed(0.1),
+            "ArrowDown" => change_speed(-0.1),
+            "ArrowLeft" => seek(-5.0),
+            "ArrowRight" => seek(5.0),
+            "m" | "M" => mirrored.update(|mirrored| *mirrored = !*mirrored),
+            "Home" => restart(),
+            "Escape" => open.set(false),
+            _ => return,
+        }
+
+        ev.prevent_default();
+    });
+    on_cleanup(move || handle.remove());
+
+    let control =
+        "px-3 py-2 rounded cursor-pointer text-gray-300 hover:text-white hover:bg-gray-700";
+
+    view! {
+        <div class=move || format!("fixed z-50 inset-0 flex flex-col bg-black text-white {}", if open.get() { "" } else { "hidden" }) role="dialog" aria-label="Teleprompter">
+            <div _ref=scroller class="flex-1 overflow-hidden">
+                <div class="max-w-4xl mx-auto px-8 py-[50vh] text-5xl leading-relaxed whitespace-pre-wrap" style=move || if mirrored.get() { "transform: scaleX(-1)" } else { "" }>
+                    {move || open.get().then(|| state.text.get())}
+                </div>
+            </div>
+            <div class="absolute top-1/2 left-0 right-0 border-t-2 border-indigo-500 opacity-50 pointer-events-none"></div>
+            <div class="flex justify-between items-center px-4 py-2 bg-gray-900">
+                <div class="flex items-center space-x-1">
+                    <button type="button" class=control title="Play or pause (Space)" on:click=move |_| playing.update(|playing| *playing = !*playing)>
+                        <i class=move || if playing.get() { "fa-solid fa-pause" } else { "fa-solid fa-play" }></i>
+                        <span class="sr-only">{move || if playing.get() { "Pause" } else { "Play" }}</span>
+                    </button>
+                    <button type="button" class=control title="Restart (Home)" on:click=move |_| restart()>
+                        <i class="fa-solid fa-backward-fast"></i>
+                        <span class="sr-only">{"Restart"}</span>
+                    </button>
+                    <button type="button" class=control title="Slower (Down)" on:click=move |_| change_speed(-0.1)>
+                        <i class="fa-solid fa-minus"></i>
+                        <span class="sr-only">{"Slower"}</span>
+                    </button>
+                    <span class="w-24 text-center" title="Speed relative to the speaking profile">
+                        {move || format!("{:.1}× · {} wpm", speed.get(), (f64::from(state.speed_profiles.get().speaking.rate) * speed.get()).round())}
+                    </span>
+                    <button type="button" class=control title="Faster (Up)" on:click=move |_| change_speed(0.1)>
+                        <i class="fa-solid fa-plus"></i>
+                        <span class="sr-only">{"Faster"}</span>
+                    </button>
+                    <button type="button" class=move || format!("{control} {}", if mirrored.get() { "bg-gray-700" } else { "" }) title="Mirror (M)" on:click=move |_| mirrored.update(|mirrored| *mirrored = !*mirrored)>
+                        <i class="fa-solid fa-left-right"></i>
+                        <span class="sr-only">{"Mirror"}</span>
+                    </button>
+                </div>
+                <span class="font-mono" title="Time elapsed and the estimate at the current speed">
+                    {move || format!("{} / {}", format_clock(elapsed.get()), format_clock(estimated.get().div_f64(speed.get())))}
+                </span>
+                <button type="button" class=control title="Close (Escape)" on:click=move |_| open.set(false)>
+                    <i class="fa-solid fa-xmark"></i>
+                    <span class="sr-only">{"Close Teleprompter"}</span>
+                </button>
+            </div>
+        </div>
+    }
+}
+
+#[component]
+pub fn script_panel() -> impl IntoView {
+    let state = use_context::<GlobalState>().unwrap_or_default();
+
+    move || {
+        if !state.script_mode.get() {
+            return None;
+        }
+
+        let timings = state.script_timings();
+        let total: Duration = timings.iter().map(|timing| timing.duration).sum();
+
+        Some(view! {
+            <div class="mb-4 bg-white p-3 rounded-md border-2 border-gray-700 dark:bg-gray-800">
+                <div class="flex justify-between items-baseline">
+                    <div class="text-3xl mt-2 mb-4 h5">{"Script"}</div>
+                    <span title="Total speaking time including pauses">{format_clock(total)}</span>
+                </div>
+                <div class="relative overflow-auto max-h-56">
+                    <table class="w-full text-sm">
+                        <thead>
+                            <tr class="text-left text-xs uppercase">
+                                <th class="px-2">{"At"}</th>
+                                <th class="px-2">{"Slide"}</th>
+                                <th class="px-2 text-right">{"Words"}</th>
+                                <th class="px-2 text-right">{"Length"}</th>
+                            </tr>
+                        </thead>
+                        <tbody>
+                            {
+                                timings.into_iter().enumerate().map(|(index, timing)| {
+                                    let (start, end) = (timing.slide.start, timing.slide.end);
+                                    let jump = move |ev: MouseEvent| {
+                                        ev.prevent_default();
+                                        editor::select_range(&state.text.get_untracked(), start, end);
+                                    };
+                                    let details = format!(
+                                        "{:.1}s of pauses, {} stage {}",
+                                        timing.slide.pause,
+                                        timing.slide.directions,
+                                        if timing.slide.directions == 1 { "direction" } else { "directions" }
+                                    );
+
+                                    view! {
+                                        <tr class=if index % 2 == 0 { "bg-gray-300 dark:bg-gray-800" } else { "bg-white dark:bg-gray-800" } title=details>
+                                            <td class="px-2 font-mono">{format_clock(timing.starts_at)}</td>
+                                            <td class="px-2">
+                                                <a href="#" class="hover:underline" on:click=jump>{timing.slide.title}</a>
+                                            </td>
+                                            <td class="px-2 text-right">{timing.words}</td>
+                                            <td class="px-2 text-right">{format_duration_short(timing.duration)}</td>
+                                        </tr>
+                                    }
+                                }).collect::<Vec<_>>()
+                            }
+                        </tbody>
+                    </table>
+                </div>
+            </div>
+        })
+    }
+}
+
+#[component]
+pub fn outline_panel() -> impl IntoView {
+    let state = use_context::<GlobalState>().unwrap_or_default();
+
+    move || {
+        let sections = state.sections();
+        if sections.is_empty() {
+            return None;
+        }
+
+        let total_words = state.word_count();
+        let words_per_minute = state.reading_words_per_minute() as u32;
+
+        Some(view! {
+            <div class="mb-4 bg-white p-3 rounded-md border-2 border-gray-700 dark:bg-gray-800">
+                <div class="text-3xl mt-2 mb-4 h5">{"Outline"}</div>
+                <div class="relative overflow-auto max-h-56 text-sm">
+                    {
+                        sections.into_iter().map(|section| outline_node(state, section, total_words, words_per_minute)).collect::<Vec<_>>()
+                    }
+                </div>
+            </div>
+        })
+    }
+}
+
+fn outline_node(
+    state: GlobalState,
+    section: Section,
+    total_words: usize,
+    words_per_minute: u32,
+) -> View {
+    let words = state.section_word_count(&section);
+    let share = if total_words == 0 {
+        0.0
+    } else {
+        words as f64 / total_words as f64 * 100.0
+    };
+    let (start, end) = (section.start, section.end);
+    let jump = move |ev: MouseEvent| {
+        ev.prevent_default();
+        editor::select_range(&state.text.get_untracked(), start, end);
+    };
+
+    let row = view! {
+        <span class="inline-flex justify-between items-center w-11/12">
+            <button type="button" class="truncate text-left hover:underline" on:click=jump>{section.title}</button>
+            <span class="flex items-baseline space-x-2 whitespace-nowrap text-gray-700 dark:text-white">
+                <span class="font-semibold">{words}</span>
+                <span class="text-xs">{format_duration_short(calculate_duration(words, words_per_minute))}</span>
+                <span class="text-xs w-12 text-right">{format!("{share:.1}%")}</span>
+            </span>
+        </span>
+    };
+
+    if section.children.is_empty() {
+        view! { <div class="pl-4 py-0.5">{row}</div> }.into_view()
+    } else {
+        view! {
+            <details open class="py-0.5">
+                <summary class="cursor-pointer">{row}</summary>
+                <div class="pl-3">
+                    {
+                        section.children.into_iter().map(|child| outline_node(state, child, total_words, words_per_minute)).collect::<Vec<_>>()
+                    }
+                </div>
+            </details>
+        }
+        .into_view()
+    }
+}
+
+#[component]
+pub fn subtitles_panel() -> impl IntoView {
+    let state = use_context::<GlobalState>().unwrap_or_default();
+
+    let update_max_characters_per_second = move |ev| {
+        let value: String = event_target_value(&ev);
+        if let Ok(value) = value.parse::<f64>() {
+            state
+                .subtitle_limits
+                .update(|limits| limits.max_characters_per_second = value);
+        }
+    };
+
+    let update_max_words_per_minute = move |ev| {
+        let value: String = event_target_value(&ev);
+        if let Ok(value) = value.parse::<f64>() {
+            state
+                .subtitle_limits
+                .update(|limits| limits.max_words_per_minute = value);
+        }
+    };
+
+    move || {
+        let cues = state.subtitles.get();
+        if cues.is_empty() {
+            return None;
+        }
+
+        let limits = state.subtitle_limits.get();
+        let flagged = cues.iter().filter(|cue| limits.exceeded_by(cue)).count();
+
+        Some(view! {
+            <div class="mb-4 bg-white p-3 rounded-md border-2 border-gray-700 dark:bg-gray-800">
+                <div class="flex justify-between items-center mt-2 mb-4">
+                    <div class="text-3xl h5">{"Subtitles"}</div>
+                    <button type="button" class="p-2 text-gray-500 rounded cursor-pointer hover:text-gray-900 hover:bg-gray-100 dark:text-gray-400 dark:hover:text-white dark:hover:bg-gray-600" on:click=move |_| state.subtitles.set(Vec::new())>
+                        <i class="fa-solid fa-xmark"></i>
+                        <span class="sr-only">{ "Close Subtitles" }</span>
+                    </button>
+                </div>
+                <div class="text-sm mb-2">{format!("{} cues, {} over the reading speed limit", cues.len(), flagged)}</div>
+                <div class="flex space-x-2 mb-4 text-xs uppercase">
+                    <label class="w-1/2">
+                        {"Max CPS"}
+                        <input type="number" step="0.5" min="1" class="bg-gray-50 border border-gray-300 text-gray-900 text-sm rounded-lg block w-full p-1.5 dark:bg-gray-700 dark:border-gray-600 dark:text-white" prop:value=limits.max_characters_per_second on:change=update_max_characters_per_second />
+                    </label>
+                    <label class="w-1/2">
+                        {"Max WPM"}
+                        <input type="number" step="5" min="1" class="bg-gray-50 border border-gray-300 text-gray-900 text-sm rounded-lg block w-full p-1.5 dark:bg-gray-700 dark:border-gray-600 dark:text-white" prop:value=limits.max_words_per_minute on:change=update_max_words_per_minute />
+                    </label>
+                </div>
+                <div class="relative overflow-auto max-h-56">
+                    <table class="w-full text-xs text-right">
+                        <thead class="uppercase">
+                            <tr>
+                                <th class="text-left">{"Start"}</th>
+                                <th>{"Words"}</th>
+                                <th>{"Chars"}</th>
+                                <th>{"WPM"}</th>
+                                <th>{"CPS"}</th>
+                            </tr>
+                        </thead>
+                        <tbody>
+                            {
+                                cues.iter().map(|cue| {
+                                    view! {
+                                        <tr class=if limits.exceeded_by(cue) { "bg-red-200 dark:bg-red-900" } else { "" } title=cue.text.clone()>
+                                            <td class="text-left">{format_timestamp(cue.start)}</td>
+                                            <td>{cue.word_count()}</td>
+                                            <td>{cue.character_count()}</td>
+                                            <td>{format!("{:.0}", cue.words_per_minute())}</td>
+                                            <td>{format!("{:.1}", cue.characters_per_second())}</td>
+                                        </tr>
+                                    }
+                                }).collect::<Vec<_>>()
+                            }
+                        </tbody>
+                    </table>
+                </div>
+            </div>
+        })
+    }
+}
+
+/// Source words of the files imported for quoting, by how much work they
+/// need, weighted with the document's discount grid.
+#[component]
+pub fn quote_panel() -> impl IntoView {
+    let state = use_context::<GlobalState>().unwrap_or_default();
+
+    let grid_input = move |label: &'static str, field: fn(&mut DiscountGrid) -> &mut f64| {
+        view! {
+            <label class="w-1/4">
+                {label}
+                <input type="number" step="5" min="0" class="bg-gray-50 border border-gray-300 text-gray-900 text-sm rounded-lg block w-full p-1.5 dark:bg-gray-700 dark:border-gray-600 dark:text-white"
+                    prop:value=move || *field(&mut state.discount_grid.get())
+                    on:change=move |ev| {
+                        if let Ok(value) = event_target_value(&ev).parse::<f64>() {
+                            state.discount_grid.update(|grid| *field(grid) = value.max(0.0));
+                        }
+                    } />
+            </label>
+        }
+    };
+
+    let export = move |_| {
+        let files = state.quote_files.get_untracked();
+        let grid = state.discount_grid.get_untracked();
+        let counts = quote::analyze(&files);
+        let total = quote::total(&counts);
+        let record = |name: &str, counts: &WordCounts| {
+            (
+                name.to_owned(),
+                counts.segments,
+                counts.new,
+                counts.fuzzy,
+                counts.repetitions,
+                counts.translated,
+                counts.total(),
+                format!("{:.1}", counts.weighted(&grid)),
+            )
+        };
+
+        download_csv(
+            "word-count-analysis.csv",
+            &[
+                "File",
+                "Segments",
+                "New",
+                "Fuzzy",
+                "Repetitions",
+                "Translated",
+                "Total",
+                "Weighted",
+            ],
+            files
+                .iter()
+                .zip(&counts)
+                .map(|(file, counts)| record(&file.name, counts))
+                .chain([record("Total", &total)]),
+        );
+    };
+
+    move || {
+        let files = state.quote_files.get();
+        if files.is_empty() {
+            return None;
+        }
+
+        let grid = state.discount_grid.get();
+        let counts = quote::analyze(&files);
+        let total = quote::total(&counts);
+        let row = |name: String, counts: &WordCounts, class: &'static str| {
+            view! {
+                <tr class=class>
+                    <td class="text-left overflow-hidden overflow-ellipsis" title=name.clone()>{name}</td>
+                    <td>{counts.new}</td>
+                    <td>{counts.fuzzy}</td>
+                    <td>{counts.repetitions}</td>
+                    <td>{counts.translated}</td>
+                    <td>{format!("{:.0}", counts.weighted(&grid))}</td>
+                </tr>
+            }
+        };
+
+        Some(view! {
+            <div class="mb-4 bg-white p-3 rounded-md border-2 border-gray-700 dark:bg-gray-800">
+                <div class="flex justify-between items-center mt-2 mb-4">
+                    <div class="text-3xl h5">{"Word Count Analysis"}</div>
+                    <div class="flex">
+                        <button type="button" class="p-2 text-gray-500 rounded cursor-pointer hover:text-gray-900 hover:bg-gray-100 dark:text-gray-400 dark:hover:text-white dark:hover:bg-gray-600" on:click=export>
+                            <img class="w-4 h-4" src="public/icons/download.svg" alt="Export CSV" height="48" width="48" />
+                            <span class="sr-only">{ "Export CSV" }</span>
+                        </button>
+                        <button type="button" class="p-2 text-gray-500 rounded cursor-pointer hover:text-gray-900 hover:bg-gray-100 dark:text-gray-400 dark:hover:text-white dark:hover:bg-gray-600" on:click=move |_| state.quote_files.set(Vec::new())>
+                            <i class="fa-solid fa-xmark"></i>
+                            <span class="sr-only">{ "Close Word Count Analysis" }</span>
+                        </button>
+                    </div>
+                </div>
+                <div class="text-sm mb-2">{format!("{} files, {} segments, {} source words", files.len(), total.segments, total.total())}</div>
+                <div class="flex space-x-2 mb-4 text-xs uppercase" title="Share of the full rate charged, in percent">
+                    {grid_input("New %", |grid| &mut grid.new)}
+                    {grid_input("Fuzzy %", |grid| &mut grid.fuzzy)}
+                    {grid_input("Reps %", |grid| &mut grid.repetitions)}
+                    {grid_input("Done %", |grid| &mut grid.translated)}
+                </div>
+                <div class="relative overflow-auto max-h-56">
+                    <table class="w-full text-xs text-right table-fixed">
+                        <thead class="uppercase">
+                            <tr>
+                                <th class="text-left w-2/6">{"File"}</th>
+                                <th>{"New"}</th>
+                                <th>{"Fuzzy"}</th>
+                                <th>{"Reps"}</th>
+                                <th>{"Done"}</th>
+                                <th>{"Weighted"}</th>
+                            </tr>
+                        </thead>
+                        <tbody>
+                            {
+                                files.iter().zip(&counts).map(|(file, counts)| row(file.name.clone(), counts, "")).collect::<Vec<_>>()
+                            }
+                            {row(String::from("Total"), &total, "font-semibold border-t-2 border-gray-700")}
+                        </tbody>
+                    </table>
+                </div>
+            </div>
+        })
+    }
+}
+
+/// Draws the highlighted ranges of the text behind the transparent editor,
+/// with the text itself invisible so that only the marks show. `layer` is
+/// kept scrolled with the editor.
+#[component]
+pub fn editor_highlights(layer: NodeRef<Div>) -> impl IntoView {
+    let state = use_context::<GlobalState>().unwrap_or_default();
+
+    let content = move || {
+        let highlights = editor::merge_ranges(state.highlights());
+        if highlights.is_empty() {
+            return Vec::new();
+        }
+
+        let text = state.text.get();
+        let mut views = Vec::new();
+        let mut position = 0;
+
+        for range in highlights {
+            views.push(text[position..range.start].to_owned().into_view());
+            views.push(view! { <mark class="text-transparent bg-yellow-200 rounded-sm dark:bg-yellow-700">{text[range.clone()].to_owned()}</mark> }.into_view());
+            position = range.end;
+        }
+
+        // A trailing space keeps a final empty line as tall as the editor's.
+        views.push(format!("{} ", &text[position..]).into_view());
+        views
+    };
+
+    view! {
+        <div _ref=layer class="absolute inset-0 p-2 border-2 border-transparent overflow-hidden whitespace-pre-wrap break-words text-transparent pointer-events-none" style="scrollbar-gutter: stable" aria-hidden="true">
+            {content}
+        </div>
+    }
+}
+
+/// Lists words, phrases and sentence openings used again soon after, and
+/// can highlight them in the editor.
+#[component]
+pub fn repetition_panel() -> impl IntoView {
+    let state = use_context::<GlobalState>().unwrap_or_default();
+
+    let update_window = move |ev| {
+        if let Ok(window) = event_target_value(&ev).parse::<usize>() {
+            state
+                .repetition_options
+                .update(|options| options.window = window);
+        }
+    };
+
+    let toggle_highlight = move |_| {
+        state
+            .repetition_options
+            .update(|options| options.highlight = !options.highlight);
+    };
+
+    view! {
+        <div class="mb-4 bg-white p-3 rounded-md border-2 border-gray-700 dark:bg-gray-800">
+            <div class="flex justify-between items-center mt-2 mb-4">
+                <div class="text-3xl h5">{"Repetitions"}</div>
+                <button type="button" title="Highlight in the editor"
+                    class=move || format!("p-2 rounded cursor-pointer hover:text-gray-900 hover:bg-gray-100 dark:hover:text-white dark:hover:bg-gray-600 {}", if state.repetition_options.get().highlight { "text-yellow-500" } else { "text-gray-500 dark:text-gray-400" })
+                    on:click=toggle_highlight>
+                    <i class="fa-solid fa-highlighter"></i>
+                    <span class="sr-only">{ "Highlight Repetitions" }</span>
+                </button>
+            </div>
+            <label class="flex items-center space-x-2 mb-4 text-xs uppercase">
+                <span>{"Within"}</span>
+                <input type="number" step="5" min="1" class="w-20 bg-gray-50 border border-gray-300 text-gray-900 text-sm rounded-lg p-1.5 dark:bg-gray-700 dark:border-gray-600 dark:text-white" prop:value=move || state.repetition_options.get().window on:change=update_window />
+                <span>{"words"}</span>
+            </label>
+            {
+                move || {
+                    let text = state.text.get();
+                    let repetitions = state.repetitions();
+                    if repetitions.is_empty() {
+                        return view! { <><p>{"No repetitions nearby."}</p></> }.into_view();
+                    }
+
+                    view! {
+                        <div class="text-sm mb-2">{format!("{} repetitions", repetitions.len())}</div>
+                        <ul class="relative overflow-auto max-h-56 text-sm">
+                            {
+                                repetitions.into_iter().map(|repetition| {
+                                    let first = editor::line_number(&text, repetition.first.start);
+                                    let second = editor::line_number(&text, repetition.second.start);
+                                    let lines = if first == second { format!("line {first}") } else { format!("lines {first} and {second}") };
+                                    let apart = match repetition.kind {
+                                        RepetitionKind::Opening if repetition.distance == 0 => String::from("consecutive sentences"),
+                                        RepetitionKind::Opening => format!("{} sentences apart", repetition.distance),
+                                        _ => format!("{} words apart", repetition.distance),
+                                    };
+                                    let range = repetition.second.clone();
+
+                                    view! {
+                                        <li class="px-2 py-1 border-b border-gray-300 cursor-pointer hover:bg-gray-100 dark:border-gray-600 dark:hover:bg-gray-700" on:click=move |_| editor::select_range(&state.text.get_untracked(), range.start, range.end)>
+                                            <div class="flex justify-between">
+                                                <span class="font-semibold">{repetition.text}</span>
+                                                <span class="text-xs uppercase">{repetition.kind.label()}</span>
+                                            </div>
+                                            <div class="text-xs">{format!("{lines}, {apart}")}</div>
+                                        </li>
+                                    }
+                                }).collect::<Vec<_>>()
+                            }
+                        </ul>
+                    }.into_view()
+                }
+            }
+        </div>
+    }
+}
+
+/// Lists sentences and paragraphs that appear more than once, exactly or
+/// nearly, with a link to each instance.
+#[component]
+pub fn duplicate_panel() -> impl IntoView {
+    let state = use_context::<GlobalState>().unwrap_or_default();
+
+    let update_similarity = move |ev| {
+        if let Ok(percent) = event_target_value(&ev).parse::<f64>() {
+            state
+                .duplicate_options
+                .update(|options| options.similarity = (percent / 100.0).clamp(0.0, 1.0));
+        }
+    };
+
+    let toggle_highlight = move |_| {
+        state
+            .duplicate_options
+            .update(|options| options.highlight = !options.highlight);
+    };
+
+    let toggle_open = move |_| state.duplicates_open.update(|open| *open = !*open);
+
+    view! {
+        <div class="mb-4 bg-white p-3 rounded-md border-2 border-gray-700 dark:bg-gray-800">
+            <div class="flex justify-between items-center mt-2 mb-4">
+                <div class="text-3xl h5">{"Duplicates"}</div>
+                <div class="flex">
+                    <button type="button" title="Highlight in the editor"
+                        class=move || format!("p-2 rounded cursor-pointer hover:text-gray-900 hover:bg-gray-100 dark:hover:text-white dark:hover:bg-gray-600 {}", if state.duplicate_options.get().highlight { "text-yellow-500" } else { "text-gray-500 dark:text-gray-400" })
+                        on:click=toggle_highlight>
+                        <i class="fa-solid fa-highlighter"></i>
+                        <span class="sr-only">{ "Highlight Duplicates" }</span>
+                    </button>
+                    <button type="button" class="p-2 text-gray-500 rounded cursor-pointer hover:text-gray-900 hover:bg-gray-100 dark:text-gray-400 dark:hover:text-white dark:hover:bg-gray-600"
+                        title=move || if state.duplicates_open.get() { "Hide the duplicates" } else { "Find duplicates" }
+                        on:click=toggle_open>
+                        <i class=move || if state.duplicates_open.get() { "fa-solid fa-chevron-up" } else { "fa-solid fa-chevron-down" }></i>
+                        <span class="sr-only">{ "Show Duplicates" }</span>
+                    </button>
+                </div>
+            </div>
+            <div class:hidden=move || !state.duplicates_open.get()>
+                <label class="flex items-center space-x-2 mb-4 text-xs uppercase" title="The share of distinct words two passages must have in common">
+                    <span>{"Similarity"}</span>
+                    <input type="number" step="5" min="0" max="100" class="w-20 bg-gray-50 border border-gray-300 text-gray-900 text-sm rounded-lg p-1.5 dark:bg-gray-700 dark:border-gray-600 dark:text-white" prop:value=move || (state.duplicate_options.get().similarity * 100.0).round() on:change=update_similarity />
+                    <span>{"% or more"}</span>
+                </label>
+                {
+                    move || {
+                        if !state.duplicates_open.get() {
+                            return ().into_view();
+                        }
+
+                        let text = state.text.get();
+                        let groups = state.duplicates();
+                        if groups.is_empty() {
+                            return view! { <><p>{"No duplicate sentences or paragraphs."}</p></> }.into_view();
+                        }
+
+                        view! {
+                            <ul class="relative overflow-auto max-h-56 text-sm">
+                                {
+                                    groups.into_iter().map(|group| {
+                                        let original = &text[group.instances[0].clone()];
+                                        let preview: String = original.chars().take(DUPLICATE_PREVIEW_CHARACTERS).collect();
+                                        let preview = if preview.len() < original.len() { format!("{preview}…") } else { preview };
+                                        let match_label = if group.similarity >= 1.0 { String::from("exact") } else { format!("{:.0}% similar", group.similarity * 100.0) };
+                                        let summary = format!("{} · {} · {}", group.kind.label(), if group.copies() == 1 { String::from("1 copy") } else { format!("{} copies", group.copies()) }, match_label);
+                                        let links = group.instances.into_iter().map(|range| {
+                                            let line = editor::line_number(&text, range.start);
+                                            view! {
+                                                <button type="button" class="px-2 mr-1 mb-1 text-xs bg-gray-200 hover:bg-gray-300 rounded-md dark:bg-gray-700 dark:hover:bg-gray-600" on:click=move |_| editor::select_range(&state.text.get_untracked(), range.start, range.end)>
+                                                    {format!("Line {line}")}
+                                                </button>
+                                            }
+                                        }).collect::<Vec<_>>();
+
+                                        view! {
+                                            <li class="px-2 py-1 border-b border-gray-300 dark:border-gray-600">
+                                                <div class="italic">{preview}</div>
+                                                <div class="text-xs uppercase mb-1">{summary}</div>
+                                                <div class="flex flex-wrap">{links}</div>
+                                            </li>
+                                        }
+                                    }).collect::<Vec<_>>()
+                                }
+                            </ul>
+                        }.into_view()
+                    }
+                }
+            </div>
+        </div>
+    }
+}
+
+/// Per-paragraph statistics under a strip chart of paragraph lengths, with
+/// the dialogue in each shaded, to show the pacing of the document.
+#[component]
+pub fn paragraph_panel() -> impl IntoView {
+    let state = use_context::<GlobalState>().unwrap_or_default();
+
+    move || {
+        let metrics = state.paragraph_metrics();
+        if metrics.is_empty() {
+            return None;
+        }
+
+        let count = metrics.len();
+        let words: usize = metrics.iter().map(|paragraph| paragraph.words).sum();
+        let longest = metrics
+            .iter()
+            .map(|paragraph| paragraph.words)
+            .max()
+            .unwrap_or(0)
+            .max(1);
+        let dialogue: f64 = metrics
+            .iter()
+            .map(|paragraph| paragraph.dialogue_ratio * paragraph.words as f64)
+            .sum::<f64>()
+            / words.max(1) as f64;
+
+        let bars = metrics.iter().map(|paragraph| {
+            let range = paragraph.range.clone();
+            let height = paragraph.words as f64 / longest as f64 * 100.0;
+            let summary = format!(
+                "Paragraph {}: {} words, {} sentences, {:.0}% dialogue",
+                paragraph.index,
+                paragraph.words,
+                paragraph.sentences,
+                paragraph.dialogue_ratio * 100.0
+            );
+
+            view! {
+                <div class="flex-1 min-w-px max-w-3 h-full flex flex-col justify-end cursor-pointer hover:opacity-75" title=summary on:click=move |_| editor::select_range(&state.text.get_untracked(), range.start, range.end)>
+                    <div class="flex flex-col" style=format!("height: {height:.1}%")>
+                        <div class="bg-yellow-400" style=format!("height: {:.1}%", paragraph.dialogue_ratio * 100.0)></div>
+                        <div class="flex-1 bg-indigo-500"></div>
+                    </div>
+                </div>
+            }
+        }).collect::<Vec<_>>();
+
+        let rows = metrics.into_iter().map(|paragraph| {
+            let range = paragraph.range.clone();
+
+            view! {
+                <tr class="cursor-pointer hover:bg-gray-100 dark:hover:bg-gray-700" on:click=move |_| editor::select_range(&state.text.get_untracked(), range.start, range.end)>
+                    <td class="text-left">{paragraph.index}</td>
+                    <td>{paragraph.words}</td>
+                    <td>{paragraph.sentences}</td>
+                    <td>{format!("{:.1}", paragraph.average_sentence_length())}</td>
+                    <td>{format!("{:.0}%", paragraph.dialogue_ratio * 100.0)}</td>
+                </tr>
+            }
+        }).collect::<Vec<_>>();
+
+        Some(view! {
+            <div class="mb-4 bg-white p-3 rounded-md border-2 border-gray-700 dark:bg-gray-800">
+                <div class="text-3xl mt-2 mb-4 h5">{"Paragraphs"}</div>
+                <div class="border-b-2 border-gray-700 flex justify-between mb-4">
+                    <div class="w-1/3">
+                        <div class="uppercase text-xs">{"Paragraphs"}</div>
+                        <span class="text-2xl text-black dark:text-white">{count}</span>
+                    </div>
+                    <div class="w-1/3">
+                        <div class="uppercase text-xs">{"Avg. Words"}</div>
+                        <span class="text-2xl text-black dark:text-white">{format!("{:.1}", words as f64 / count as f64)}</span>
+                    </div>
+                    <div class="w-1/3">
+                        <div class="uppercase text-xs">{"Dialogue"}</div>
+                        <span class="text-2xl text-black dark:text-white">{format!("{:.0}%", dialogue * 100.0)}</span>
+                    </div>
+                </div>
+                <div class="flex items-end gap-px h-24 mb-1">{bars}</div>
+                <div class="flex gap-4 text-xs mb-4">
+                    <span><span class="inline-block w-2 h-2 mr-1 bg-indigo-500"></span>{"Narration"}</span>
+                    <span><span class="inline-block w-2 h-2 mr-1 bg-yellow-400"></span>{"Dialogue"}</span>
+                </div>
+                <div class="relative overflow-auto max-h-56">
+                    <table class="w-full text-xs text-right">
+                        <thead class="uppercase">
+                            <tr>
+                                <th class="text-left">{"#"}</th>
+                                <th>{"Words"}</th>
+                                <th>{"Sent."}</th>
+                                <th>{"Avg."}</th>
+                                <th>{"Dialogue"}</th>
+                            </tr>
+                        </thead>
+                        <tbody>{rows}</tbody>
+                    </table>
+                </div>
+            </div>
+        })
+    }
+}
+
+/// Every sentence with its length, syllables and grade, sortable by each
+/// column and filtered by the words it contains.
+#[component]
+pub fn sentence_panel() -> impl IntoView {
+    let state = use_context::<GlobalState>().unwrap_or_default();
+    let column = create_rw_signal(SentenceColumn::default());
+    let descending = create_rw_signal(false);
+    let filter = create_rw_signal(String::new());
+    let metrics = create_memo(move |_| state.sentence_metrics());
+
+    let sort_by = move |clicked: SentenceColumn| {
+        if column.get_untracked() == clicked {
+            descending.update(|descending| *descending = !*descending);
+        } else {
+            column.set(clicked);
+            // Larger values are the interesting ones, except for the order.
+            descending.set(clicked != SentenceColumn::Index);
+        }
+    };
+
+    // The sentences that contain the filter, in the chosen order.
+    let shown = create_memo(move |_| {
+        let query = filter.get().to_lowercase();
+        let mut shown = state.text.with(|text| {
+            metrics
+                .get()
+                .into_iter()
+                .filter(|sentence| text[sentence.range.clone()].to_lowercase().contains(&query))
+                .collect::<Vec<_>>()
+        });
+        sentences::sort(&mut shown, column.get(), descending.get());
+        shown
+    });
+
+    let headers = move || {
+        SentenceColumn::ALL.into_iter().map(|header| {
+            let arrow = match (column.get() == header, descending.get()) {
+                (false, _) => "",
+                (true, false) => " ▲",
+                (true, true) => " ▼",
+            };
+            let align = if header == SentenceColumn::Index { "text-left" } else { "" };
+
+            view! {
+                <th class=format!("cursor-pointer select-none hover:underline {align}") on:click=move |_| sort_by(header)>
+                    {format!("{}{arrow}", header.label())}
+                </th>
+            }
+        }).collect::<Vec<_>>()
+    };
+
+    let rows = move || {
+        let text = state.text.get();
+
+        shown.get().into_iter().map(|sentence| {
+            let preview = text[sentence.range.clone()].to_owned();
+            let range = sentence.range.clone();
+
+            view! {
+                <tr class="cursor-pointer hover:bg-gray-100 dark:hover:bg-gray-700" title=preview on:click=move |_| editor::select_range(&state.text.get_untracked(), range.start, range.end)>
+                    <td class="text-left">{sentence.index}</td>
+                    <td>{sentence.words}</td>
+                    <td>{sentence.characters}</td>
+                    <td>{sentence.syllables}</td>
+                    <td>{format!("{:.1}", sentence.grade)}</td>
+                </tr>
+            }
+        }).collect::<Vec<_>>()
+    };
+
+    let summary = move || {
+        let total = metrics.with(Vec::len);
+        let shown = shown.with(Vec::len);
+        if shown == total {
+            format!("{total} sentences")
+        } else {
+            format!("{shown} of {total} sentences")
+        }
+    };
+
+    // The filter input stays in place while the rows change, so it keeps
+    // the focus as the user types.
+    view! {
+        <div class="mb-4 bg-white p-3 rounded-md border-2 border-gray-700 dark:bg-gray-800" class:hidden=move || metrics.with(Vec::is_empty)>
+            <div class="text-3xl mt-2 mb-4 h5">{"Sentences"}</div>
+            <input type="search" placeholder="Filter sentences" class="bg-gray-50 border border-gray-300 text-gray-900 text-sm rounded-lg block w-full p-1.5 mb-2 dark:bg-gray-700 dark:border-gray-600 dark:text-white"
+                prop:value=move || filter.get()
+                on:input=move |ev| filter.set(event_target_value(&ev)) />
+            <div class="text-sm mb-2">{summary}</div>
+            <div class="relative overflow-auto max-h-56">
+                <table class="w-full text-xs text-right">
+                    <thead class="uppercase">
+                        <tr>{headers}</tr>
+                    </thead>
+                    <tbody>{rows}</tbody>
+                </table>
+            </div>
+        </div>
+    }
+}
+
+/// How much of a duplicate the panel shows.
+const DUPLICATE_PREVIEW_CHARACTERS: usize = 80;
+
+#[component]
+pub fn toggle_switch(label: &'static str, value: RwSignal<bool>) -> impl IntoView {
+    let input_ref = create_node_ref::<Input>();
+    let onchange = move |e| value.set(event_target_checked(&e));
+
+    create_effect(move |_| {
+        if let Some(inputs) = input_ref.clone().get() {
+            println!("{}", value.get());
+            inputs.set_checked(value.get());
+        }
+    });
+
+    view! {
+        <label class="relative flex justify-between items-center group p-2 text-xl">
+            {label}
+            <input _ref=input_ref type="checkbox" class="absolute left-1/2 -translate-x-1/2 w-full h-full peer appearance-none rounded-md" checked=value.get() on:change=onchange />
+            <span class="w-16 h-10 flex items-center flex-shrink-0 ml-4 p-1 bg-gray-300 rounded-full duration-300 ease-in-out peer-checked:bg-green-400 after:w-8 after:h-8 after:bg-white after:rounded-full after:shadow-md after:duration-300 peer-checked:after:translate-x-6 group-hover:after:translate-x-1"></span>
+        </label>
+    }
+}
+
+fn format_duration(duration: Duration) -> impl IntoView {
+    let total_seconds = duration.as_secs();
+    let minutes = total_seconds / 60;
+    let seconds = total_seconds % 60;
+
+    if minutes > 60 {
+        let hours = minutes / 60;
+        let remaining_minutes = minutes % 60;
+
+        view! {
+            <>
+                <div class="flex flex-nowrap text-center space-x-2">
+                    <span class="text-3xl text-black dark:text-white">
+                        {hours}
+                        <div class="text-xs">{ if hours != 1 { "hrs" } else { "hr" } }</div>
+                    </span>
+                    <span class="text-3xl text-black dark:text-white">
+                        {remaining_minutes}
+                        <div class="text-xs">{ if remaining_minutes != 1 { "mins" } else { "min" } }</div>
+                    </span>
+                    <span class="text-3xl text-black dark:text-white">
+                        {seconds}
+                        <div class="text-xs">{  if seconds != 1 { "secs" } else { "sec" } }</div>
+                    </span>
+                </div>
+            </>
+        }
+    } else if minutes > 0 {
+        view! {
+            <>
+                <div class="flex flex-nowrap text-center space-x-2">
+                    <span class="text-3xl text-black dark:text-white">
+                        {minutes}
+                        <div class="text-xs">{ if minutes != 1 { "mins" } else { "min" } }</div>
+                    </span>
+                    <span class="text-3xl text-black dark:text-white">
+                        {seconds}
+                        <div class="text-xs">{ if seconds != 1 { "secs" } else { "sec" } }</div>
+                    </span>
+                </div>
+            </>
+        }
+    } else {
+        view! {
+            <>
+                <div class="flex flex-nowrap text-center">
+                    <span class="text-3xl text-black dark:text-white">
+                        {seconds}
+                        <div class="text-xs">{"secs"}</div>
+                    </span>
+                </div>
+            </>
+        }
+    }
+}
+
+fn format_duration_short(duration: Duration) -> String {
+    let total_seconds = duration.as_secs();
+
+    match (
+        total_seconds / 3600,
+        total_seconds / 60 % 60,
+        total_seconds % 60,
+    ) {
+        (0, 0, seconds) => format!("{seconds}s"),
+        (0, minutes, seconds) => format!("{minutes}m {seconds}s"),
+        (hours, minutes, _) => format!("{hours}h {minutes}m"),
+    }
+}
+
+/// A position in a talk, as `m:ss` or `h:mm:ss`.
+fn format_clock(duration: Duration) -> String {
+    let total_seconds = duration.as_secs();
+
+    match total_seconds / 3600 {
+        0 => format!("{}:{:02}", total_seconds / 60, total_seconds % 60),
+        hours => format!(
+            "{hours}:{:02}:{:02}",
+            total_seconds / 60 % 60,
+            total_seconds % 60
+        ),
+    }
+}
+
+fn format_timestamp(duration: Duration) -> String {
+    let total_seconds = duration.as_secs();
+
+    format!(
+        "{:02}:{:02}:{:02}.{:03}",
+        total_seconds / 3600,
+        total_seconds / 60 % 60,
+        total_seconds % 60,
+        duration.subsec_millis()
+    )
 }
 
 pub fn calculate_duration(word_count: usize, words_per_minute: u32) -> Duration {
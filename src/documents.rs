@@ -0,0 +1,353 @@
+use js_sys::Date;
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use serde_json::Value;
+use wasm_bindgen::JsValue;
+
+use crate::{
+    app::{StatisticOption, WORD_REGEX},
+    characters::{CharacterExclusions, CharacterUnit},
+    duplicates::DuplicateOptions,
+    goals::Goal,
+    markup::{self, MarkupOptions, SourceFormat},
+    platforms::Platform,
+    quote::DiscountGrid,
+    repetition::RepetitionOptions,
+    speed::{Activity, SpeedProfile, SpeedProfiles},
+    storage::Storage,
+    subtitles::{Cue, ReadingSpeedLimits},
+    translation::Translation,
+};
+
+/// Statistics settings that each document keeps for itself.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DocumentSettings {
+    pub match_case: bool,
+    pub statistics_options: Vec<StatisticOption>,
+    pub source_format: SourceFormat,
+    pub markup_options: MarkupOptions,
+    pub heading_pattern: String,
+    pub subtitle_limits: ReadingSpeedLimits,
+    #[serde(default)]
+    pub goal: Option<Goal>,
+    #[serde(default)]
+    pub platform: Option<Platform>,
+    #[serde(default)]
+    pub character_unit: CharacterUnit,
+    #[serde(default)]
+    pub character_exclusions: CharacterExclusions,
+    #[serde(default)]
+    pub speed_profiles: SpeedProfiles,
+    #[serde(default)]
+    pub adjust_reading_time: bool,
+    #[serde(default)]
+    pub script_mode: bool,
+    /// How many times longer than its source a translated segment may be.
+    #[serde(default = "default_max_expansion")]
+    pub max_expansion: f64,
+    #[serde(default)]
+    pub discount_grid: DiscountGrid,
+    #[serde(default)]
+    pub repetition_options: RepetitionOptions,
+    #[serde(default)]
+    pub duplicate_options: DuplicateOptions,
+}
+
+fn default_max_expansion() -> f64 {
+    1.3
+}
+
+impl Default for DocumentSettings {
+    fn default() -> Self {
+        Self {
+            match_case: false,
+            statistics_options: vec![
+                StatisticOption::Words,
+                StatisticOption::Characters,
+                StatisticOption::Sentences,
+                StatisticOption::Paragraphs,
+                StatisticOption::ReadingTime,
+                StatisticOption::SpeakingTime,
+            ],
+            source_format: SourceFormat::default(),
+            markup_options: MarkupOptions::default(),
+            heading_pattern: String::new(),
+            subtitle_limits: ReadingSpeedLimits::default(),
+            goal: None,
+            platform: None,
+            character_unit: CharacterUnit::default(),
+            character_exclusions: CharacterExclusions::default(),
+            speed_profiles: SpeedProfiles::default(),
+            adjust_reading_time: false,
+            script_mode: false,
+            max_expansion: default_max_expansion(),
+            discount_grid: DiscountGrid::default(),
+            repetition_options: RepetitionOptions::default(),
+            duplicate_options: DuplicateOptions::default(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Document {
+    pub id: u64,
+    pub name: String,
+    pub text: String,
+    /// Milliseconds since the Unix epoch.
+    pub modified: f64,
+    #[serde(default)]
+    pub subtitles: Vec<Cue>,
+    #[serde(default)]
+    pub translation: Translation,
+    #[serde(default)]
+    pub settings: DocumentSettings,
+}
+
+impl Document {
+    pub fn new(id: u64, name: String) -> Self {
+        Self {
+            id,
+            name,
+            text: String::new(),
+            modified: Date::now(),
+            subtitles: Vec::new(),
+            translation: Translation::default(),
+            settings: DocumentSettings::default(),
+        }
+    }
+
+    pub fn word_count(&self) -> usize {
+        let content = markup::prose(
+            &self.text,
+            self.settings.source_format,
+            self.settings.markup_options,
+        );
+
+        WORD_REGEX.find_iter(&content).count()
+    }
+
+    pub fn modified_label(&self) -> String {
+        Date::new(&JsValue::from_f64(self.modified))
+            .to_locale_string("default", &JsValue::UNDEFINED)
+            .into()
+    }
+}
+
+pub fn next_id(documents: &[Document]) -> u64 {
+    documents
+        .iter()
+        .map(|document| document.id + 1)
+        .max()
+        .unwrap_or(1)
+}
+
+/// Each document is kept under its own key, so that one that cannot be read
+/// does not take the others with it and an edit rewrites only its document.
+pub const STORAGE_PREFIX: &str = "document:";
+/// Where documents that cannot be read are set aside, unchanged.
+pub const UNREADABLE_PREFIX: &str = "unreadable_document:";
+/// The key of libraries saved before each document had a key of its own.
+const LIBRARY_KEY: &str = "documents";
+
+fn key(id: u64) -> String {
+    format!("{STORAGE_PREFIX}{id}")
+}
+
+/// Restores the library, the id of the open document and how many documents
+/// could not be read. Those are moved under [`UNREADABLE_PREFIX`] as they
+/// were, so that nothing writes over them. A library saved before documents
+/// existed is migrated from the single-document keys.
+pub fn load(storage: &Storage) -> (Vec<Document>, u64, usize) {
+    let mut unreadable = split_library(storage);
+    let mut documents = Vec::new();
+
+    for key in storage.keys() {
+        let Some(id) = key.strip_prefix(STORAGE_PREFIX) else {
+            continue;
+        };
+        let Some(raw) = storage.get_item(&key) else {
+            continue;
+        };
+
+        let document = serde_json::from_str::<Value>(&raw)
+            .ok()
+            .and_then(|mut value| {
+                if let Some(settings) = value.get_mut("settings") {
+                    upgrade_settings(settings, storage);
+                }
+                serde_json::from_value::<Document>(value).ok()
+            });
+
+        match document {
+            Some(document) if document.id.to_string() == id => documents.push(document),
+            _ => {
+                storage.set_item(&format!("{UNREADABLE_PREFIX}{id}"), &raw);
+                storage.remove_item(&key);
+                unreadable += 1;
+            }
+        }
+    }
+    documents.sort_by_key(|document| document.id);
+
+    if documents.is_empty() {
+        documents.push(migrate(storage));
+    }
+
+    let current = storage
+        .get_item("current_document")
+        .and_then(|s| s.parse::<u64>().ok())
+        .filter(|id| documents.iter().any(|document| document.id == *id))
+        .unwrap_or(documents[0].id);
+
+    (documents, current, unreadable)
+}
+
+/// Moves each document of a library saved under a single key to a key of
+/// its own, and returns how many had to be set aside.
+fn split_library(storage: &Storage) -> usize {
+    let Some(raw) = storage.get_item(LIBRARY_KEY) else {
+        return 0;
+    };
+
+    let Ok(Value::Array(documents)) = serde_json::from_str::<Value>(&raw) else {
+        storage.set_item(&format!("{UNREADABLE_PREFIX}{LIBRARY_KEY}"), &raw);
+        storage.remove_item(LIBRARY_KEY);
+        return 1;
+    };
+
+    let mut unreadable = 0;
+    for (index, document) in documents.iter().enumerate() {
+        let raw = document.to_string();
+        match document.get("id").and_then(Value::as_u64) {
+            Some(id) => storage.set_item(&key(id), &raw),
+            None => {
+                storage.set_item(&format!("{UNREADABLE_PREFIX}{LIBRARY_KEY}:{index}"), &raw);
+                unreadable += 1;
+            }
+        }
+    }
+    storage.remove_item(LIBRARY_KEY);
+
+    unreadable
+}
+
+/// Writes the documents that differ from `previous`, the library as last
+/// saved, and removes the ones that were deleted since.
+pub fn save(storage: &Storage, documents: &[Document], previous: &[Document], current: u64) {
+    for document in documents {
+        let saved = previous.iter().find(|saved| saved.id == document.id);
+        if saved != Some(document) {
+            storage.set_item(&key(document.id), &serde_json::to_string(document).unwrap());
+        }
+    }
+
+    for saved in previous {
+        if !documents.iter().any(|document| document.id == saved.id) {
+            storage.remove_item(&key(saved.id));
+        }
+    }
+
+    storage.set_item("current_document", &current.to_string());
+}
+
+fn migrate(storage: &Storage) -> Document {
+    let item = |key: &str| storage.get_item(key);
+    let defaults = DocumentSettings::default();
+
+    let mut legacy = serde_json::json!({
+        "statistics_options": json::<Value>(storage, "statistics_options")
+            .unwrap_or_else(|| serde_json::to_value(&defaults.statistics_options).unwrap()),
+    });
+    upgrade_settings(&mut legacy, storage);
+
+    Document {
+        text: item("text").unwrap_or_default(),
+        subtitles: json(storage, "subtitles").unwrap_or_default(),
+        settings: DocumentSettings {
+            match_case: item("match_case")
+                .or_else(|| item("case_insensitive"))
+                .map_or_else(|| false, |s| s.parse::<bool>().unwrap_or(true)),
+            statistics_options: serde_json::from_value(legacy["statistics_options"].take())
+                .unwrap_or(defaults.statistics_options),
+            source_format: json(storage, "source_format").unwrap_or_default(),
+            markup_options: json(storage, "markup_options").unwrap_or_default(),
+            heading_pattern: item("heading_pattern").unwrap_or_default(),
+            subtitle_limits: json(storage, "subtitle_limits").unwrap_or_default(),
+            goal: None,
+            platform: None,
+            character_unit: CharacterUnit::default(),
+            character_exclusions: CharacterExclusions::default(),
+            speed_profiles: serde_json::from_value(legacy["speed_profiles"].take())
+                .unwrap_or_default(),
+            adjust_reading_time: false,
+            script_mode: false,
+            max_expansion: defaults.max_expansion,
+            discount_grid: DiscountGrid::default(),
+            repetition_options: RepetitionOptions::default(),
+            duplicate_options: DuplicateOptions::default(),
+        },
+        ..Document::new(1, String::from("Untitled"))
+    }
+}
+
+/// Moves the reading, speaking and hand writing speeds of settings saved
+/// before speed profiles into profiles. They were kept in the statistics
+/// options, as in `{"ReadingTime": 275}`, and in storage for the statistics
+/// that were turned off.
+fn upgrade_settings(settings: &mut Value, storage: &Storage) {
+    if !settings.is_object() || settings.get("speed_profiles").is_some() {
+        return;
+    }
+
+    let mut profiles = SpeedProfiles::default();
+    let mut set_rate = |activity: Activity, rate: u32| {
+        if activity.default_profile().rate != rate {
+            *profiles.get_mut(activity) = SpeedProfile::custom(rate);
+        }
+    };
+
+    for (key, activity) in [
+        ("reading_time", Activity::Reading),
+        ("speaking_time", Activity::Speaking),
+        ("hand_writing_time", Activity::HandWriting),
+    ] {
+        if let Some(rate) = storage.get_item(key).and_then(|s| s.parse::<u32>().ok()) {
+            set_rate(activity, rate);
+        }
+    }
+
+    if let Some(options) = settings
+        .get_mut("statistics_options")
+        .and_then(Value::as_array_mut)
+    {
+        for option in options {
+            let Some((name, rate)) = option
+                .as_object()
+                .and_then(|object| object.iter().next())
+                .map(|(name, rate)| (name.clone(), rate.as_u64()))
+            else {
+                continue;
+            };
+
+            let activity = match name.as_str() {
+                "ReadingTime" => Some(Activity::Reading),
+                "SpeakingTime" => Some(Activity::Speaking),
+                "HandWritingTime" => Some(Activity::HandWriting),
+                _ => None,
+            };
+
+            if let (Some(activity), Some(rate)) = (activity, rate) {
+                set_rate(activity, rate as u32);
+            }
+
+            *option = Value::String(name);
+        }
+    }
+
+    settings["speed_profiles"] = serde_json::to_value(profiles).unwrap();
+}
+
+fn json<T: DeserializeOwned>(storage: &Storage, key: &str) -> Option<T> {
+    storage
+        .get_item(key)
+        .and_then(|s| serde_json::from_str(&s).ok())
+}
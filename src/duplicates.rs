@@ -0,0 +1,233 @@
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::{BTreeSet, HashMap},
+    ops::Range,
+};
+
+use crate::{
+    app::{SENTENCE_REGEX, WORD_REGEX},
+    editor::trim_range,
+    paragraphs,
+};
+
+/// Sentences and paragraphs shorter than this many words only count when
+/// they match exactly, as short ones look alike too easily.
+const MIN_NEAR_DUPLICATE_WORDS: usize = 4;
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct DuplicateOptions {
+    /// How alike two sentences or paragraphs must be to count as duplicates,
+    /// as the share of distinct words they have in common, from 0 to 1.
+    pub similarity: f64,
+    /// Whether the editor highlights the duplicates.
+    pub highlight: bool,
+}
+
+impl Default for DuplicateOptions {
+    fn default() -> Self {
+        Self {
+            similarity: 0.8,
+            highlight: false,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DuplicateKind {
+    Sentence,
+    Paragraph,
+}
+
+impl DuplicateKind {
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::Sentence => "Sentence",
+            Self::Paragraph => "Paragraph",
+        }
+    }
+}
+
+/// Sentences or paragraphs that are the same or nearly so.
+#[derive(Debug, Clone, PartialEq)]
+pub struct DuplicateGroup {
+    pub kind: DuplicateKind,
+    /// Byte ranges into the text, in order. The first is the original.
+    pub instances: Vec<Range<usize>>,
+    /// How alike the least similar copy is to the original, 1 for exact
+    /// copies once whitespace and case are ignored.
+    pub similarity: f64,
+}
+
+impl DuplicateGroup {
+    pub fn copies(&self) -> usize {
+        self.instances.len() - 1
+    }
+}
+
+struct Passage {
+    range: Range<usize>,
+    normalized: String,
+    words: BTreeSet<String>,
+}
+
+/// Finds duplicated paragraphs, then duplicated sentences outside the
+/// copies of those paragraphs, each in the order their originals occur.
+pub fn find(text: &str, similarity: f64) -> Vec<DuplicateGroup> {
+    let mut groups = group(
+        text,
+        DuplicateKind::Paragraph,
+        paragraphs::ranges(text),
+        similarity,
+    );
+
+    let copied: Vec<Range<usize>> = groups
+        .iter()
+        .flat_map(|group| group.instances[1..].iter().cloned())
+        .collect();
+    let sentences = SENTENCE_REGEX
+        .find_iter(text)
+        .map(|sentence| trim_range(text, sentence.range()))
+        .filter(|sentence| {
+            !copied
+                .iter()
+                .any(|copy| copy.start <= sentence.start && sentence.end <= copy.end)
+        })
+        .collect();
+
+    groups.extend(group(text, DuplicateKind::Sentence, sentences, similarity));
+    groups
+}
+
+fn group(
+    text: &str,
+    kind: DuplicateKind,
+    ranges: Vec<Range<usize>>,
+    similarity: f64,
+) -> Vec<DuplicateGroup> {
+    let passages: Vec<Passage> = ranges
+        .into_iter()
+        .filter(|range| !range.is_empty())
+        .map(|range| {
+            let passage = &text[range.clone()];
+            Passage {
+                normalized: passage
+                    .split_whitespace()
+                    .collect::<Vec<_>>()
+                    .join(" ")
+                    .to_lowercase(),
+                words: WORD_REGEX
+                    .find_iter(passage)
+                    .map(|word| word.as_str().to_lowercase())
+                    .collect(),
+                range,
+            }
+        })
+        .collect();
+
+    // Words ranked rarest first, so that the first few words of a passage
+    // in this order are likely to be ones few other passages use.
+    let mut frequency: HashMap<&str, usize> = HashMap::new();
+    for passage in &passages {
+        for word in &passage.words {
+            *frequency.entry(word).or_default() += 1;
+        }
+    }
+    // Two passages this alike share at least one word among the first
+    // `len - ceil(similarity * len) + 1` of each in this order, so only
+    // originals sharing one of those need a closer look. The small margin
+    // keeps rounding from cutting the prefix short.
+    let prefixes: Vec<Vec<&str>> = passages
+        .iter()
+        .map(|passage| {
+            if passage.words.len() < MIN_NEAR_DUPLICATE_WORDS {
+                return Vec::new();
+            }
+
+            let mut words: Vec<&str> = passage.words.iter().map(String::as_str).collect();
+            words.sort_by_key(|word| (frequency[word], *word));
+            let required = (similarity * words.len() as f64 - 1e-9).ceil().max(0.0) as usize;
+            words.truncate(words.len() - required.min(words.len()) + 1);
+            words
+        })
+        .collect();
+
+    // The original of each group and the group's instances, by index.
+    let mut groups: Vec<(usize, Vec<usize>, f64)> = Vec::new();
+    let mut exact: HashMap<&str, usize> = HashMap::new();
+    // The groups whose original has a word in its prefix, by word.
+    let mut candidates: HashMap<&str, Vec<usize>> = HashMap::new();
+
+    for (index, passage) in passages.iter().enumerate() {
+        if let Some(group) = exact.get(passage.normalized.as_str()) {
+            groups[*group].1.push(index);
+            continue;
+        }
+
+        let eligible = passage.words.len() >= MIN_NEAR_DUPLICATE_WORDS;
+        let near = if similarity <= 0.0 {
+            // Any two passages are alike enough, so the first group will do.
+            groups
+                .iter()
+                .position(|(original, _, _)| {
+                    passages[*original].words.len() >= MIN_NEAR_DUPLICATE_WORDS
+                })
+                .filter(|_| eligible)
+        } else {
+            let mut possible: Vec<usize> = prefixes[index]
+                .iter()
+                .filter_map(|word| candidates.get(word))
+                .flatten()
+                .copied()
+                .collect();
+            possible.sort_unstable();
+            possible.dedup();
+
+            possible.into_iter().find(|group| {
+                let original = &passages[groups[*group].0].words;
+                // Passages can share no more words than the smaller has.
+                let sizes = original.len().min(passage.words.len()) as f64
+                    / original.len().max(passage.words.len()) as f64;
+                sizes >= similarity && jaccard(original, &passage.words) >= similarity
+            })
+        };
+
+        match near {
+            Some(group) => {
+                let alike = jaccard(&passages[groups[group].0].words, &passage.words);
+                groups[group].1.push(index);
+                groups[group].2 = groups[group].2.min(alike);
+            }
+            None => {
+                exact.insert(&passage.normalized, groups.len());
+                for word in &prefixes[index] {
+                    candidates.entry(*word).or_default().push(groups.len());
+                }
+                groups.push((index, vec![index], 1.0));
+            }
+        }
+    }
+
+    groups
+        .into_iter()
+        .filter(|(_, instances, _)| instances.len() > 1)
+        .map(|(_, instances, similarity)| DuplicateGroup {
+            kind,
+            instances: instances
+                .into_iter()
+                .map(|index| passages[index].range.clone())
+                .collect(),
+            similarity,
+        })
+        .collect()
+}
+
+/// The share of distinct words used in either passage that both use.
+fn jaccard(a: &BTreeSet<String>, b: &BTreeSet<String>) -> f64 {
+    let (shorter, longer) = if a.len() <= b.len() { (a, b) } else { (b, a) };
+    if longer.is_empty() {
+        return 1.0;
+    }
+
+    let shared = shorter.intersection(longer).count();
+    shared as f64 / (a.len() + b.len() - shared) as f64
+}
@@ -0,0 +1,66 @@
+use leptos::document;
+use std::ops::Range;
+use wasm_bindgen::JsCast;
+use web_sys::HtmlTextAreaElement;
+
+pub const EDITOR_ID: &str = "editor";
+
+/// Converts a byte offset into `text` to the UTF-16 offset the DOM uses for
+/// selections.
+pub fn utf16_offset(text: &str, byte_offset: usize) -> u32 {
+    text[..byte_offset.min(text.len())].encode_utf16().count() as u32
+}
+
+/// Selects `start..end` (byte offsets into `text`) in the editor and scrolls
+/// it into view.
+pub fn select_range(text: &str, start: usize, end: usize) {
+    let Some(editor) = document()
+        .get_element_by_id(EDITOR_ID)
+        .and_then(|element| element.dyn_into::<HtmlTextAreaElement>().ok())
+    else {
+        return;
+    };
+
+    let total_lines = text.lines().count().max(1);
+    let lines_before = text[..start.min(text.len())]
+        .lines()
+        .count()
+        .saturating_sub(1);
+
+    editor.focus().ok();
+    editor
+        .set_selection_range(utf16_offset(text, start), utf16_offset(text, end))
+        .ok();
+    editor.set_scroll_top(
+        (f64::from(editor.scroll_height()) * lines_before as f64 / total_lines as f64) as i32,
+    );
+}
+
+/// The line, counting from one, that the byte offset `offset` is on.
+pub fn line_number(text: &str, offset: usize) -> usize {
+    text[..offset.min(text.len())].matches('\n').count() + 1
+}
+
+/// Narrows `range` of `text` to leave out whitespace at either end.
+pub fn trim_range(text: &str, range: Range<usize>) -> Range<usize> {
+    let slice = &text[range.clone()];
+    let start = range.start + (slice.len() - slice.trim_start().len());
+    let end = range.end - (slice.len() - slice.trim_end().len());
+
+    start..end.max(start)
+}
+
+/// Sorts `ranges` and joins the ones that overlap or touch.
+pub fn merge_ranges(mut ranges: Vec<Range<usize>>) -> Vec<Range<usize>> {
+    ranges.sort_by_key(|range| range.start);
+    let mut merged: Vec<Range<usize>> = Vec::with_capacity(ranges.len());
+
+    for range in ranges {
+        match merged.last_mut() {
+            Some(last) if range.start <= last.end => last.end = last.end.max(range.end),
+            _ => merged.push(range),
+        }
+    }
+
+    merged
+}
@@ -0,0 +1,125 @@
+use std::collections::HashMap;
+use unicode_segmentation::UnicodeSegmentation;
+
+use crate::characters::CharacterClass;
+
+/// Relative frequencies of the letters in English text, in percent.
+const ENGLISH_FREQUENCIES: [f64; 26] = [
+    8.167, 1.492, 2.782, 4.253, 12.702, 2.228, 2.015, 6.094, 6.966, 0.153, 0.772, 4.025, 2.406,
+    6.749, 7.507, 1.929, 0.095, 5.987, 6.327, 9.056, 2.758, 0.978, 2.360, 0.150, 1.974, 0.074,
+];
+
+/// The share of the most frequent English letter, `e`.
+pub const ENGLISH_MAXIMUM: f64 = 12.702;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct CharacterFrequency {
+    pub grapheme: String,
+    pub count: usize,
+    /// Share of all counted characters, in percent.
+    pub percent: f64,
+}
+
+impl CharacterFrequency {
+    /// The share of this letter in typical English text, if it is one of
+    /// the 26 basic Latin letters.
+    pub fn expected_english(&self) -> Option<f64> {
+        let mut chars = self.grapheme.chars();
+        let letter = chars.next()?.to_ascii_lowercase();
+
+        if chars.next().is_some() || !letter.is_ascii_lowercase() {
+            return None;
+        }
+
+        Some(ENGLISH_FREQUENCIES[(letter as u8 - b'a') as usize])
+    }
+}
+
+/// Counts each grapheme of `text`, most frequent first.
+pub fn frequencies(text: &str, fold_case: bool, letters_only: bool) -> Vec<CharacterFrequency> {
+    let mut counts: HashMap<String, usize> = HashMap::new();
+
+    for grapheme in text.graphemes(true) {
+        if letters_only && !CharacterClass::Letters.contains(grapheme) {
+            continue;
+        }
+
+        let grapheme = if fold_case {
+            grapheme.to_lowercase()
+        } else {
+            grapheme.to_owned()
+        };
+
+        *counts.entry(grapheme).or_default() += 1;
+    }
+
+    let total: usize = counts.values().sum();
+    let mut frequencies: Vec<CharacterFrequency> = counts
+        .into_iter()
+        .map(|(grapheme, count)| CharacterFrequency {
+            grapheme,
+            count,
+            percent: count as f64 / total as f64 * 100.0,
+        })
+        .collect();
+
+    frequencies.sort_by(|a, b| {
+        b.count
+            .cmp(&a.count)
+            .then_with(|| a.grapheme.cmp(&b.grapheme))
+    });
+    frequencies
+}
+
+/// Shannon entropy in bits per character.
+pub fn entropy(frequencies: &[CharacterFrequency]) -> f64 {
+    frequencies
+        .iter()
+        .map(|frequency| frequency.percent / 100.0)
+        .filter(|p| *p > 0.0)
+        .map(|p| -p * p.log2())
+        .sum()
+}
+
+/// Chi-squared distance of the basic Latin letters from English; the lower,
+/// the more the text looks like English. `None` without any such letter.
+pub fn english_chi_squared(frequencies: &[CharacterFrequency]) -> Option<f64> {
+    let mut observed = [0usize; 26];
+    for frequency in frequencies {
+        if let Some(letter) = frequency
+            .grapheme
+            .chars()
+            .next()
+            .filter(|_| frequency.expected_english().is_some())
+        {
+            observed[(letter.to_ascii_lowercase() as u8 - b'a') as usize] += frequency.count;
+        }
+    }
+
+    let total: usize = observed.iter().sum();
+    if total == 0 {
+        return None;
+    }
+
+    Some(
+        observed
+            .iter()
+            .zip(ENGLISH_FREQUENCIES)
+            .map(|(observed, percent)| {
+                let expected = total as f64 * percent / 100.0;
+                (*observed as f64 - expected).powi(2) / expected
+            })
+            .sum(),
+    )
+}
+
+/// How a grapheme is shown in the table, with invisible characters named.
+pub fn display(grapheme: &str) -> String {
+    match grapheme {
+        " " => String::from("␣ (space)"),
+        "\n" | "\r\n" => String::from("↵ (line break)"),
+        "\t" => String::from("⇥ (tab)"),
+        "\u{a0}" => String::from("⍽ (no-break space)"),
+        _ => grapheme.to_owned(),
+    }
+}
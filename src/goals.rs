@@ -0,0 +1,94 @@
+use js_sys::Date;
+use serde::{Deserialize, Serialize};
+use wasm_bindgen::JsValue;
+
+const DAY_MILLISECONDS: f64 = 86_400_000.0;
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum GoalMetric {
+    #[default]
+    Words,
+    Characters,
+    /// Measured in minutes.
+    ReadingTime,
+}
+
+impl GoalMetric {
+    pub const ALL: [Self; 3] = [Self::Words, Self::Characters, Self::ReadingTime];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::Words => "Words",
+            Self::Characters => "Characters",
+            Self::ReadingTime => "Reading Time",
+        }
+    }
+
+    pub fn unit(self) -> &'static str {
+        match self {
+            Self::Words => "words",
+            Self::Characters => "characters",
+            Self::ReadingTime => "minutes",
+        }
+    }
+}
+
+/// A length a document should reach, or stay within when `maximum` is set.
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Goal {
+    pub metric: GoalMetric,
+    /// The target, or the lower bound in range mode.
+    pub target: usize,
+    /// The upper bound in range mode.
+    pub maximum: Option<usize>,
+    /// A `YYYY-MM-DD` date, as entered in a date input.
+    pub deadline: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GoalStatus {
+    InProgress,
+    Reached,
+    OverLimit,
+}
+
+impl Goal {
+    /// The upper bound, unless range mode is off or the maximum is still 0,
+    /// as when range mode was just turned on.
+    pub fn upper_bound(&self) -> Option<usize> {
+        self.maximum.filter(|maximum| *maximum > 0)
+    }
+
+    pub fn status(&self, value: f64) -> GoalStatus {
+        if self
+            .upper_bound()
+            .is_some_and(|maximum| value > maximum as f64)
+        {
+            GoalStatus::OverLimit
+        } else if value >= self.target as f64 {
+            GoalStatus::Reached
+        } else {
+            GoalStatus::InProgress
+        }
+    }
+
+    /// How far along the bar `value` is, from 0 to 1. In range mode the bar
+    /// spans up to the maximum so that the minimum sits part way along it.
+    pub fn progress(&self, value: f64) -> f64 {
+        let end = self.upper_bound().unwrap_or(self.target).max(1) as f64;
+        (value / end).clamp(0.0, 1.0)
+    }
+
+    /// Whole days until the end of the deadline day; negative once it has
+    /// passed.
+    pub fn days_left(&self) -> Option<i64> {
+        let deadline = self.deadline.as_deref()?;
+        let end = Date::new(&JsValue::from_str(&format!("{deadline}T23:59:59"))).get_time();
+
+        if end.is_nan() {
+            return None;
+        }
+
+        Some(((end - Date::now()) / DAY_MILLISECONDS).ceil() as i64)
+    }
+}
@@ -0,0 +1,261 @@
+use js_sys::Date;
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+use wasm_bindgen::JsValue;
+
+use crate::{
+    app::{PARAGRAPH_REGEX, SENTENCE_REGEX, WORD_REGEX},
+    components::calculate_duration,
+    readability::Readability,
+    storage::Storage,
+};
+
+/// How often the open document is snapshotted while it changes.
+pub const AUTOMATIC_INTERVAL: Duration = Duration::from_secs(5 * 60);
+/// Older automatic snapshots are dropped beyond this many; named versions
+/// are always kept.
+const MAX_AUTOMATIC_REVISIONS: usize = 50;
+/// Versions saved before restoring another are dropped beyond this many,
+/// oldest first.
+const MAX_RESTORE_BACKUPS: usize = 10;
+/// Line diffs larger than this (old lines × new lines) fall back to showing
+/// the changed region as a single removal and addition.
+const MAX_DIFF_CELLS: usize = 4_000_000;
+
+pub const STORAGE_PREFIX: &str = "revisions:";
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Revision {
+    /// Milliseconds since the Unix epoch.
+    pub created: f64,
+    pub text: String,
+    /// Set for versions saved by hand; automatic snapshots have no name.
+    pub name: Option<String>,
+    /// Whether the version was saved on its own before restoring another.
+    #[serde(default)]
+    pub before_restore: bool,
+}
+
+impl Revision {
+    pub fn created_label(&self) -> String {
+        Date::new(&JsValue::from_f64(self.created))
+            .to_locale_string("default", &JsValue::UNDEFINED)
+            .into()
+    }
+
+    pub fn label(&self) -> String {
+        self.name.clone().unwrap_or_else(|| self.created_label())
+    }
+}
+
+pub fn storage_key(document_id: u64) -> String {
+    format!("{STORAGE_PREFIX}{document_id}")
+}
+
+pub fn load(storage: &Storage, document_id: u64) -> Vec<Revision> {
+    storage
+        .get_item(&storage_key(document_id))
+        .and_then(|s| serde_json::from_str(&s).ok())
+        .unwrap_or_default()
+}
+
+pub fn save(storage: &Storage, document_id: u64, revisions: &[Revision]) {
+    storage.set_item(
+        &storage_key(document_id),
+        &serde_json::to_string(revisions).unwrap(),
+    );
+}
+
+/// Appends a snapshot of `text` unless it is identical to the latest one.
+/// Returns whether a revision was added.
+pub fn record(revisions: &mut Vec<Revision>, text: String, name: Option<String>) -> bool {
+    if name.is_none() && revisions.last().is_some_and(|last| last.text == text) {
+        return false;
+    }
+
+    revisions.push(Revision {
+        created: Date::now(),
+        text,
+        name,
+        before_restore: false,
+    });
+    prune(
+        revisions,
+        |revision| revision.name.is_none(),
+        MAX_AUTOMATIC_REVISIONS,
+    );
+
+    true
+}
+
+/// Appends `text` as the version named `name` that restoring another
+/// replaces. Only the latest few of these are kept.
+pub fn record_before_restore(revisions: &mut Vec<Revision>, text: String, name: String) {
+    revisions.push(Revision {
+        created: Date::now(),
+        text,
+        name: Some(name),
+        before_restore: true,
+    });
+    prune(
+        revisions,
+        |revision| revision.before_restore,
+        MAX_RESTORE_BACKUPS,
+    );
+}
+
+/// Drops the oldest revisions matching `kind` beyond `limit` of them.
+fn prune(revisions: &mut Vec<Revision>, kind: impl Fn(&Revision) -> bool, limit: usize) {
+    let count = revisions.iter().filter(|revision| kind(revision)).count();
+    let mut excess = count.saturating_sub(limit);
+    revisions.retain(|revision| {
+        if excess > 0 && kind(revision) {
+            excess -= 1;
+            return false;
+        }
+
+        true
+    });
+}
+
+/// Whether enough time has passed since the latest revision for an
+/// automatic snapshot of `text`.
+pub fn is_due(revisions: &[Revision], text: &str) -> bool {
+    match revisions.last() {
+        Some(last) => {
+            last.text != text && Date::now() - last.created >= AUTOMATIC_INTERVAL.as_millis() as f64
+        }
+        None => !text.is_empty(),
+    }
+}
+
+/// The statistics that are compared between two versions.
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub struct Snapshot {
+    pub words: usize,
+    pub characters: usize,
+    pub sentences: usize,
+    pub paragraphs: usize,
+    pub reading_time: Duration,
+    pub reading_ease: f64,
+    pub grade: f64,
+}
+
+impl Snapshot {
+    pub fn of(text: &str, words_per_minute: u32) -> Self {
+        let words = WORD_REGEX.find_iter(text).count();
+        let readability = Readability::of(text);
+
+        Self {
+            words,
+            characters: text.chars().count(),
+            sentences: SENTENCE_REGEX.find_iter(text).count(),
+            paragraphs: if text.is_empty() {
+                0
+            } else {
+                PARAGRAPH_REGEX.split(text).count()
+            },
+            reading_time: calculate_duration(words, words_per_minute),
+            reading_ease: readability.reading_ease(),
+            grade: readability.grade(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Change {
+    Unchanged(String),
+    Added(String),
+    Removed(String),
+}
+
+/// A line-based diff from `old` to `new` using the longest common
+/// subsequence of lines.
+pub fn diff_lines(old: &str, new: &str) -> Vec<Change> {
+    let old: Vec<&str> = old.lines().collect();
+    let new: Vec<&str> = new.lines().collect();
+
+    let prefix = old.iter().zip(&new).take_while(|(a, b)| a == b).count();
+    let suffix = old[prefix..]
+        .iter()
+        .rev()
+        .zip(new[prefix..].iter().rev())
+        .take_while(|(a, b)| a == b)
+        .count();
+
+    let old_middle = &old[prefix..old.len() - suffix];
+    let new_middle = &new[prefix..new.len() - suffix];
+
+    let mut changes: Vec<Change> = old[..prefix]
+        .iter()
+        .map(|line| Change::Unchanged((*line).to_owned()))
+        .collect();
+
+    if old_middle.len() * new_middle.len() > MAX_DIFF_CELLS {
+        changes.extend(
+            old_middle
+                .iter()
+                .map(|line| Change::Removed((*line).to_owned())),
+        );
+        changes.extend(
+            new_middle
+                .iter()
+                .map(|line| Change::Added((*line).to_owned())),
+        );
+    } else {
+        changes.extend(lcs_diff(old_middle, new_middle));
+    }
+
+    changes.extend(
+        old[old.len() - suffix..]
+            .iter()
+            .map(|line| Change::Unchanged((*line).to_owned())),
+    );
+
+    changes
+}
+
+fn lcs_diff(old: &[&str], new: &[&str]) -> Vec<Change> {
+    let width = new.len() + 1;
+    let mut lengths = vec![0u32; (old.len() + 1) * width];
+
+    for i in (0..old.len()).rev() {
+        for j in (0..new.len()).rev() {
+            lengths[i * width + j] = if old[i] == new[j] {
+                lengths[(i + 1) * width + j + 1] + 1
+            } else {
+                lengths[(i + 1) * width + j].max(lengths[i * width + j + 1])
+            };
+        }
+    }
+
+    let mut changes = Vec::new();
+    let (mut i, mut j) = (0, 0);
+
+    while i < old.len() && j < new.len() {
+        if old[i] == new[j] {
+            changes.push(Change::Unchanged(old[i].to_owned()));
+            i += 1;
+            j += 1;
+        } else if lengths[(i + 1) * width + j] >= lengths[i * width + j + 1] {
+            changes.push(Change::Removed(old[i].to_owned()));
+            i += 1;
+        } else {
+            changes.push(Change::Added(new[j].to_owned()));
+            j += 1;
+        }
+    }
+
+    changes.extend(
+        old[i..]
+            .iter()
+            .map(|line| Change::Removed((*line).to_owned())),
+    );
+    changes.extend(
+        new[j..]
+            .iter()
+            .map(|line| Change::Added((*line).to_owned())),
+    );
+
+    changes
+}
@@ -1,13 +1,43 @@
 mod app;
+mod characters;
+mod compare;
+mod complexity;
 mod components;
+mod documents;
+mod duplicates;
+mod editor;
+mod frequency;
+mod goals;
+mod history;
+mod markup;
+mod outline;
+mod paragraphs;
+mod platforms;
+mod quote;
+mod readability;
+mod repetition;
+mod script;
+mod sentences;
+mod sessions;
+mod sms;
+mod speed;
+mod storage;
+mod subtitles;
+mod translation;
+mod undo;
 
 use app::App;
-use leptos::{mount_to_body, view};
+use leptos::{mount_to_body, spawn_local, view};
+use storage::Storage;
 
 fn main() {
-    mount_to_body(|| {
-        view! {
-            <App/>
-        }
+    spawn_local(async {
+        let storage = Storage::open().await;
+
+        mount_to_body(move || {
+            view! {
+                <App storage/>
+            }
+        });
     });
 }
@@ -0,0 +1,463 @@
+use regex::{Captures, Regex};
+use serde::{Deserialize, Serialize};
+use std::{ops::Range, sync::LazyLock};
+
+static URL_REGEX: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"(?i)\b(?:https?|ftp)://\S+|\bwww\.\S+").unwrap());
+static HTML_COMMENT_REGEX: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"(?s)<!--.*?-->").unwrap());
+static HTML_TAG_REGEX: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"<[^>]+>").unwrap());
+
+static MARKDOWN_FRONT_MATTER_REGEX: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"(?s)\A(?:---\n.*?\n(?:---|\.\.\.)|\+\+\+\n.*?\n\+\+\+)\n").unwrap()
+});
+pub(crate) static MARKDOWN_FENCE_REGEX: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"(?ms)^ {0,3}(```|~~~).*?^ {0,3}(?:```|~~~)[ \t]*$").unwrap());
+static MARKDOWN_HEADING_REGEX: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"(?m)^ {0,3}#{1,6}(?:[ \t]+(.*?))?[ \t#]*$").unwrap());
+static MARKDOWN_SETEXT_REGEX: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"(?m)^([^\n]*\S[^\n]*)\n {0,3}(?:=+|-+)[ \t]*$").unwrap());
+static MARKDOWN_RULE_REGEX: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"(?m)^ {0,3}(?:[-*_][ \t]*){3,}$").unwrap());
+static MARKDOWN_FOOTNOTE_DEFINITION_REGEX: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"(?m)^ {0,3}\[\^[^\]]+\]:[ \t]*(.*)$").unwrap());
+static MARKDOWN_FOOTNOTE_REFERENCE_REGEX: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"\[\^[^\]]+\]").unwrap());
+static MARKDOWN_LINK_DEFINITION_REGEX: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"(?m)^ {0,3}\[[^\]]+\]:[ \t]*\S+.*$").unwrap());
+pub(crate) static MARKDOWN_IMAGE_REGEX: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"!\[([^\]]*)\](?:\([^)]*\)|\[[^\]]*\])").unwrap());
+static MARKDOWN_LINK_REGEX: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"\[([^\]]*)\](?:\([^)]*\)|\[[^\]]*\])").unwrap());
+static MARKDOWN_LINK_TARGET_REGEX: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"\]\([^)]*\)").unwrap());
+static MARKDOWN_AUTOLINK_REGEX: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"<(?:[a-zA-Z][a-zA-Z0-9+.-]*:|mailto:)[^>\s]*>").unwrap());
+static MARKDOWN_INLINE_CODE_REGEX: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"(`+)[^`]+?`+").unwrap());
+pub(crate) static MARKDOWN_TABLE_ROW_REGEX: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"(?m)^[ \t]*\|.*\|[ \t]*$").unwrap());
+pub(crate) static MARKDOWN_TABLE_DIVIDER_REGEX: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"(?m)^[ \t]*\|?[ \t]*:?-+:?[ \t]*(?:\|[ \t]*:?-+:?[ \t]*)+\|?[ \t]*$").unwrap()
+});
+static MARKDOWN_BLOCK_PREFIX_REGEX: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"(?m)^[ \t]*(?:>[ \t]?)*(?:[-*+][ \t]+(?:\[[ xX]\][ \t]+)?|\d+[.)][ \t]+)?")
+        .unwrap()
+});
+static MARKDOWN_EMPHASIS_REGEX: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"\*{1,3}|\b_{1,3}|_{1,3}\b|~~").unwrap());
+
+pub(crate) static HTML_CODE_REGEXES: LazyLock<Vec<Regex>> = LazyLock::new(|| {
+    ["script", "style", "pre", "code", "template", "noscript"]
+        .iter()
+        .map(|tag| Regex::new(&format!(r"(?is)<{tag}\b[^>]*>.*?</{tag}\s*>")).unwrap())
+        .collect()
+});
+pub(crate) static HTML_IMAGE_REGEX: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"(?i)<img\b[^>]*>").unwrap());
+pub(crate) static HTML_TABLE_ROW_REGEX: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"(?is)<tr\b[^>]*>.*?</tr\s*>").unwrap());
+static HTML_HEADING_REGEX: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"(?is)<h[1-6]\b[^>]*>.*?</h[1-6]\s*>").unwrap());
+static HTML_CAPTION_REGEX: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"(?is)<figcaption\b[^>]*>.*?</figcaption\s*>|<caption\b[^>]*>.*?</caption\s*>")
+        .unwrap()
+});
+static HTML_IMAGE_ALT_REGEX: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r#"(?is)<img\b[^>]*?\balt\s*=\s*(?:"([^"]*)"|'([^']*)')[^>]*>"#).unwrap()
+});
+static HTML_FOOTNOTE_REGEXES: LazyLock<Vec<Regex>> = LazyLock::new(|| {
+    ["section", "div", "aside", "ol"]
+        .iter()
+        .map(|tag| {
+            Regex::new(&format!(
+                r#"(?is)<{tag}\b[^>]*\bclass\s*=\s*["'][^"']*footnotes?\b[^"']*["'][^>]*>.*?</{tag}\s*>"#
+            ))
+            .unwrap()
+        })
+        .collect()
+});
+static HTML_FOOTNOTE_REFERENCE_REGEX: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"(?is)<sup\b[^>]*>.*?</sup\s*>").unwrap());
+static HTML_BLOCK_TAG_REGEX: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"(?i)</?(?:p|div|br|li|ul|ol|h[1-6]|tr|td|th|table|section|article|blockquote|figure|figcaption|caption)\b[^>]*>").unwrap()
+});
+static HTML_ENTITY_REGEX: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"&(?:#(\d+)|#[xX]([0-9a-fA-F]+)|([a-zA-Z]+));").unwrap());
+
+/// Commands whose argument is a key, a path or a setting rather than prose.
+const LATEX_MARKUP_COMMANDS: [&str; 22] = [
+    "cite",
+    "citep",
+    "citet",
+    "ref",
+    "eqref",
+    "pageref",
+    "autoref",
+    "label",
+    "url",
+    "href",
+    "includegraphics",
+    "input",
+    "include",
+    "bibliography",
+    "bibliographystyle",
+    "begin",
+    "end",
+    "usepackage",
+    "documentclass",
+    "vspace",
+    "hspace",
+    "setlength",
+];
+static LATEX_DOCUMENT_REGEX: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"(?s)\\begin\{document\}(.*?)(?:\\end\{document\}|\z)").unwrap());
+static LATEX_COMMENT_REGEX: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"(?m)(^|[^\\])%.*$").unwrap());
+static LATEX_CODE_REGEX: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"(?s)\\begin\{(verbatim|lstlisting|minted|equation|align|gather|multline|displaymath|math|tikzpicture)\*?\}.*?\\end\{(?:verbatim|lstlisting|minted|equation|align|gather|multline|displaymath|math|tikzpicture)\*?\}").unwrap()
+});
+static LATEX_MATH_REGEX: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"(?s)\$\$.*?\$\$|\\\[.*?\\\]|\\\(.*?\\\)|\$[^$]+\$|\\verb\*?(?:\|[^|]*\||\+[^+]*\+|![^!]*!)").unwrap()
+});
+static LATEX_COMMAND_REGEX: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"\\([a-zA-Z]+)\*?(?:\[[^\]]*\])*\{([^{}]*)\}").unwrap());
+static LATEX_MARKUP_COMMAND_REGEX: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(&format!(
+        r"\\(?:{})\*?(?:\[[^\]]*\])*\{{[^{{}}]*\}}",
+        LATEX_MARKUP_COMMANDS.join("|")
+    ))
+    .unwrap()
+});
+static LATEX_BARE_COMMAND_REGEX: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"\\(?:[a-zA-Z]+\*?|.)").unwrap());
+
+static ASCIIDOC_COMMENT_BLOCK_REGEX: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"(?ms)^(/{4,})[ \t]*$.*?^/{4,}[ \t]*$").unwrap());
+static ASCIIDOC_LISTING_REGEX: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"(?ms)^(?:-{4,}|\.{4,}|\+{4,}|`{3})[ \t]*$.*?^(?:-{4,}|\.{4,}|\+{4,}|`{3})[ \t]*$")
+        .unwrap()
+});
+static ASCIIDOC_LINE_COMMENT_REGEX: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"(?m)^//.*$").unwrap());
+static ASCIIDOC_ATTRIBUTE_REGEX: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"(?m)^:!?[\w-]+!?:.*$|^\[[^\]]*\][ \t]*$").unwrap());
+static ASCIIDOC_HEADING_REGEX: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"(?m)^(?:=|#){1,6}[ \t]+(.*)$").unwrap());
+static ASCIIDOC_BLOCK_TITLE_REGEX: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"(?m)^\.([^.\s].*)$").unwrap());
+static ASCIIDOC_IMAGE_REGEX: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"image::?[^\[\s]*\[([^\],]*)[^\]]*\]").unwrap());
+static ASCIIDOC_FOOTNOTE_REGEX: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"footnote(?::[\w-]*)?\[([^\]]*)\]").unwrap());
+static ASCIIDOC_LINK_REGEX: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"(?:link:|xref:|mailto:|(?:https?|ftp)://)[^\[\s]*\[([^\]]*)\]|<<[^,>]*,?([^>]*)>>")
+        .unwrap()
+});
+static ASCIIDOC_TABLE_REGEX: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"(?m)^[|,:!]===[ \t]*$").unwrap());
+static ASCIIDOC_BLOCK_PREFIX_REGEX: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"(?m)^[ \t]*(?:(?:NOTE|TIP|IMPORTANT|WARNING|CAUTION):[ \t]+|[*.-]+[ \t]+|\|)")
+        .unwrap()
+});
+static ASCIIDOC_INLINE_REGEX: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"`[^`]*`|\*{1,2}|\b_{1,2}|_{1,2}\b|#{1,2}").unwrap());
+
+/// The markup language the editor text is written in.
+#[derive(Debug, Default, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+pub enum SourceFormat {
+    #[default]
+    Plain,
+    Markdown,
+    Html,
+    Latex,
+    AsciiDoc,
+}
+
+impl SourceFormat {
+    pub const ALL: [Self; 5] = [
+        Self::Plain,
+        Self::Markdown,
+        Self::Html,
+        Self::Latex,
+        Self::AsciiDoc,
+    ];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::Plain => "Plain Text",
+            Self::Markdown => "Markdown",
+            Self::Html => "HTML",
+            Self::Latex => "LaTeX",
+            Self::AsciiDoc => "AsciiDoc",
+        }
+    }
+}
+
+/// Which optional parts of a marked-up document count as prose.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+pub struct MarkupOptions {
+    pub include_headings: bool,
+    pub include_captions: bool,
+    pub include_footnotes: bool,
+}
+
+impl Default for MarkupOptions {
+    fn default() -> Self {
+        Self {
+            include_headings: true,
+            include_captions: true,
+            include_footnotes: true,
+        }
+    }
+}
+
+/// Strips the syntax of `format` from `text`, leaving only the prose that
+/// should be counted. Code, front matter, URLs and markup tokens are removed.
+pub fn prose(text: &str, format: SourceFormat, options: MarkupOptions) -> String {
+    match format {
+        SourceFormat::Plain => text.to_owned(),
+        SourceFormat::Markdown => markdown(text, options),
+        SourceFormat::Html => html(text, options),
+        SourceFormat::Latex => latex(text, options),
+        SourceFormat::AsciiDoc => asciidoc(text, options),
+    }
+}
+
+/// Blanks out the markup in `text` that is not prose, such as tags, link
+/// targets, code and commands, leaving everything else where it was so that
+/// offsets into the result are offsets into `text`.
+pub fn mask(text: &str, format: SourceFormat) -> String {
+    let regexes: Vec<&Regex> = match format {
+        SourceFormat::Plain => Vec::new(),
+        SourceFormat::Markdown => vec![
+            &MARKDOWN_FRONT_MATTER_REGEX,
+            &MARKDOWN_FENCE_REGEX,
+            &HTML_COMMENT_REGEX,
+            &MARKDOWN_LINK_DEFINITION_REGEX,
+            &MARKDOWN_LINK_TARGET_REGEX,
+            &MARKDOWN_AUTOLINK_REGEX,
+            &MARKDOWN_INLINE_CODE_REGEX,
+            &HTML_TAG_REGEX,
+            &URL_REGEX,
+        ],
+        SourceFormat::Html => [&*HTML_COMMENT_REGEX]
+            .into_iter()
+            .chain(HTML_CODE_REGEXES.iter())
+            .chain([&*HTML_TAG_REGEX, &HTML_ENTITY_REGEX, &URL_REGEX])
+            .collect(),
+        SourceFormat::Latex => vec![
+            &LATEX_CODE_REGEX,
+            &LATEX_MATH_REGEX,
+            &LATEX_MARKUP_COMMAND_REGEX,
+            &LATEX_BARE_COMMAND_REGEX,
+            &URL_REGEX,
+        ],
+        SourceFormat::AsciiDoc => vec![
+            &ASCIIDOC_COMMENT_BLOCK_REGEX,
+            &ASCIIDOC_LISTING_REGEX,
+            &ASCIIDOC_LINE_COMMENT_REGEX,
+            &ASCIIDOC_ATTRIBUTE_REGEX,
+            &URL_REGEX,
+        ],
+    };
+
+    let mut ranges: Vec<Range<usize>> = regexes
+        .into_iter()
+        .flat_map(|regex| regex.find_iter(text).map(|markup| markup.range()))
+        .collect();
+
+    // A comment starts after the character its pattern checks is not a
+    // backslash.
+    if format == SourceFormat::Latex {
+        ranges.extend(
+            LATEX_COMMENT_REGEX
+                .captures_iter(text)
+                .map(|comment| comment.get(1).unwrap().end()..comment.get(0).unwrap().end()),
+        );
+    }
+
+    let mut bytes = text.as_bytes().to_vec();
+    for range in ranges {
+        // Line breaks are kept so that lines, sentences and paragraphs stay
+        // apart.
+        for byte in &mut bytes[range] {
+            if *byte != b'\n' {
+                *byte = b' ';
+            }
+        }
+    }
+
+    // Only whole characters are blanked out.
+    String::from_utf8(bytes).unwrap()
+}
+
+fn keep_if(include: bool, value: &str) -> String {
+    if include {
+        value.to_owned()
+    } else {
+        String::new()
+    }
+}
+
+fn first_group(captures: &Captures) -> String {
+    captures
+        .iter()
+        .skip(1)
+        .flatten()
+        .next()
+        .map(|m| m.as_str().to_owned())
+        .unwrap_or_default()
+}
+
+fn markdown(text: &str, options: MarkupOptions) -> String {
+    let text = text.replace("\r\n", "\n");
+    let text = MARKDOWN_FRONT_MATTER_REGEX.replace(&text, "");
+    let text = MARKDOWN_FENCE_REGEX.replace_all(&text, "");
+    let text = HTML_COMMENT_REGEX.replace_all(&text, "");
+    let text = MARKDOWN_FOOTNOTE_DEFINITION_REGEX.replace_all(&text, |captures: &Captures| {
+        keep_if(options.include_footnotes, &captures[1])
+    });
+    let text = MARKDOWN_FOOTNOTE_REFERENCE_REGEX.replace_all(&text, "");
+    let text = MARKDOWN_LINK_DEFINITION_REGEX.replace_all(&text, "");
+    let text = MARKDOWN_SETEXT_REGEX.replace_all(&text, |captures: &Captures| {
+        keep_if(options.include_headings, &captures[1])
+    });
+    let text = MARKDOWN_RULE_REGEX.replace_all(&text, "");
+    let text = MARKDOWN_HEADING_REGEX.replace_all(&text, |captures: &Captures| {
+        keep_if(options.include_headings, &first_group(captures))
+    });
+    let text = MARKDOWN_TABLE_DIVIDER_REGEX.replace_all(&text, "");
+    let text = MARKDOWN_IMAGE_REGEX.replace_all(&text, |captures: &Captures| {
+        keep_if(options.include_captions, &captures[1])
+    });
+    let text = MARKDOWN_LINK_REGEX.replace_all(&text, "$1");
+    let text = MARKDOWN_AUTOLINK_REGEX.replace_all(&text, "");
+    let text = MARKDOWN_INLINE_CODE_REGEX.replace_all(&text, "");
+    let text = HTML_TAG_REGEX.replace_all(&text, "");
+    let text = URL_REGEX.replace_all(&text, "");
+    let text = MARKDOWN_BLOCK_PREFIX_REGEX.replace_all(&text, "");
+    let text = MARKDOWN_EMPHASIS_REGEX.replace_all(&text, "");
+
+    text.replace('|', " ")
+}
+
+fn html(text: &str, options: MarkupOptions) -> String {
+    let mut text = HTML_COMMENT_REGEX.replace_all(text, "").into_owned();
+
+    for regex in HTML_CODE_REGEXES.iter() {
+        text = regex.replace_all(&text, "").into_owned();
+    }
+
+    if !options.include_footnotes {
+        for regex in HTML_FOOTNOTE_REGEXES.iter() {
+            text = regex.replace_all(&text, "").into_owned();
+        }
+    }
+
+    let text = HTML_FOOTNOTE_REFERENCE_REGEX.replace_all(&text, "");
+    let text = if options.include_headings {
+        text
+    } else {
+        HTML_HEADING_REGEX.replace_all(&text, "")
+    };
+    let text = if options.include_captions {
+        text
+    } else {
+        HTML_CAPTION_REGEX.replace_all(&text, "")
+    };
+    let text = HTML_IMAGE_ALT_REGEX.replace_all(&text, |captures: &Captures| {
+        keep_if(options.include_captions, &first_group(captures))
+    });
+    let text = HTML_BLOCK_TAG_REGEX.replace_all(&text, "\n");
+    let text = HTML_TAG_REGEX.replace_all(&text, "");
+    let text = HTML_ENTITY_REGEX.replace_all(&text, |captures: &Captures| {
+        let code = match (captures.get(1), captures.get(2), captures.get(3)) {
+            (Some(decimal), _, _) => decimal.as_str().parse::<u32>().ok(),
+            (_, Some(hex), _) => u32::from_str_radix(hex.as_str(), 16).ok(),
+            (_, _, Some(name)) => match name.as_str() {
+                "amp" => Some('&' as u32),
+                "lt" => Some('<' as u32),
+                "gt" => Some('>' as u32),
+                "quot" => Some('"' as u32),
+                "apos" => Some('\'' as u32),
+                "nbsp" => Some('\u{a0}' as u32),
+                "mdash" => Some('\u{2014}' as u32),
+                "ndash" => Some('\u{2013}' as u32),
+                _ => None,
+            },
+            _ => None,
+        };
+
+        code.and_then(char::from_u32)
+            .map_or_else(|| captures[0].to_owned(), String::from)
+    });
+
+    URL_REGEX.replace_all(&text, "").into_owned()
+}
+
+fn latex(text: &str, options: MarkupOptions) -> String {
+    let text = text.replace("\r\n", "\n");
+    let text = LATEX_DOCUMENT_REGEX
+        .captures(&text)
+        .map_or(text.as_str(), |captures| captures.get(1).unwrap().as_str())
+        .to_owned();
+    let text = LATEX_COMMENT_REGEX.replace_all(&text, "$1");
+    let text = LATEX_CODE_REGEX.replace_all(&text, "");
+    let mut text = LATEX_MATH_REGEX.replace_all(&text, "").into_owned();
+
+    // Arguments are resolved innermost first so that formatting nested inside
+    // a footnote or caption is unwrapped before the outer command is judged.
+    loop {
+        let replaced = LATEX_COMMAND_REGEX
+            .replace_all(&text, |captures: &Captures| match &captures[1] {
+                "part" | "chapter" | "section" | "subsection" | "subsubsection" | "paragraph"
+                | "subparagraph" | "title" => keep_if(options.include_headings, &captures[2]),
+                "caption" => keep_if(options.include_captions, &captures[2]),
+                "footnote" | "footnotetext" => {
+                    keep_if(options.include_footnotes, &format!(" {} ", &captures[2]))
+                }
+                command if LATEX_MARKUP_COMMANDS.contains(&command) => String::new(),
+                _ => captures[2].to_owned(),
+            })
+            .into_owned();
+
+        if replaced == text {
+            break;
+        }
+
+        text = replaced;
+    }
+
+    let text = LATEX_BARE_COMMAND_REGEX.replace_all(&text, "");
+    let text = URL_REGEX.replace_all(&text, "");
+
+    text.replace(['{', '}', '~'], " ")
+}
+
+fn asciidoc(text: &str, options: MarkupOptions) -> String {
+    let text = text.replace("\r\n", "\n");
+    let text = ASCIIDOC_COMMENT_BLOCK_REGEX.replace_all(&text, "");
+    let text = ASCIIDOC_LISTING_REGEX.replace_all(&text, "");
+    let text = ASCIIDOC_LINE_COMMENT_REGEX.replace_all(&text, "");
+    let text = ASCIIDOC_ATTRIBUTE_REGEX.replace_all(&text, "");
+    let text = ASCIIDOC_HEADING_REGEX.replace_all(&text, |captures: &Captures| {
+        keep_if(options.include_headings, &captures[1])
+    });
+    let text = ASCIIDOC_BLOCK_TITLE_REGEX.replace_all(&text, |captures: &Captures| {
+        keep_if(options.include_captions, &captures[1])
+    });
+    let text = ASCIIDOC_IMAGE_REGEX.replace_all(&text, |captures: &Captures| {
+        keep_if(options.include_captions, &captures[1])
+    });
+    let text = ASCIIDOC_FOOTNOTE_REGEX.replace_all(&text, |captures: &Captures| {
+        keep_if(options.include_footnotes, &captures[1])
+    });
+    let text = ASCIIDOC_LINK_REGEX.replace_all(&text, |captures: &Captures| first_group(captures));
+    let text = URL_REGEX.replace_all(&text, "");
+    let text = ASCIIDOC_TABLE_REGEX.replace_all(&text, "");
+    let text = ASCIIDOC_BLOCK_PREFIX_REGEX.replace_all(&text, "");
+    let text = ASCIIDOC_INLINE_REGEX.replace_all(&text, "");
+
+    text.replace('|', " ")
+}
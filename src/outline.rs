@@ -0,0 +1,139 @@
+use regex::Regex;
+use std::sync::LazyLock;
+
+static ATX_HEADING_REGEX: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"^ {0,3}(#{1,6})[ \t]+(.*?)[ \t#]*$").unwrap());
+static FENCE_REGEX: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"^ {0,3}(```|~~~)").unwrap());
+
+/// A user's pattern for lines that count as top-level headings, compiled
+/// once. Patterns match at the start of a line.
+#[derive(Debug, Clone)]
+pub struct HeadingPattern(Regex);
+
+impl HeadingPattern {
+    /// Compiles `pattern`, or returns `None` when it is empty or invalid.
+    pub fn new(pattern: &str) -> Option<Self> {
+        if pattern.is_empty() {
+            return None;
+        }
+
+        Regex::new(&format!("^(?:{pattern})")).ok().map(Self)
+    }
+}
+
+impl PartialEq for HeadingPattern {
+    fn eq(&self, other: &Self) -> bool {
+        self.0.as_str() == other.0.as_str()
+    }
+}
+
+/// A heading and everything up to the next heading of the same or a higher
+/// level. Offsets are byte offsets into the editor text.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Section {
+    pub title: String,
+    pub level: usize,
+    pub start: usize,
+    pub end: usize,
+    pub children: Vec<Section>,
+}
+
+/// Finds Markdown `#` headings when `markdown` is set and, when `pattern` is
+/// given, lines matching it (treated as top-level headings). Text before the
+/// first heading becomes an untitled section so the outline always covers
+/// the whole document.
+pub fn sections(text: &str, markdown: bool, pattern: Option<&HeadingPattern>) -> Vec<Section> {
+    let mut headings = Vec::new();
+    let mut in_fence = false;
+    let mut offset = 0;
+
+    for line in text.split_inclusive('\n') {
+        let start = offset;
+        offset += line.len();
+        let line = line.trim_end_matches(['\r', '\n']);
+
+        if markdown && FENCE_REGEX.is_match(line) {
+            in_fence = !in_fence;
+            continue;
+        }
+
+        if in_fence {
+            continue;
+        }
+
+        if let Some(captures) = ATX_HEADING_REGEX.captures(line).filter(|_| markdown) {
+            headings.push((captures[1].len(), captures[2].to_owned(), start));
+        } else if pattern.is_some_and(|pattern| pattern.0.is_match(line)) {
+            headings.push((1, line.trim().to_owned(), start));
+        }
+    }
+
+    if headings.is_empty() {
+        return Vec::new();
+    }
+
+    let flat: Vec<Section> = headings
+        .iter()
+        .enumerate()
+        .map(|(index, (level, title, start))| {
+            let end = headings[index + 1..]
+                .iter()
+                .find(|(next_level, _, _)| next_level <= level)
+                .map_or(text.len(), |(_, _, next_start)| *next_start);
+
+            Section {
+                title: title.clone(),
+                level: *level,
+                start: *start,
+                end,
+                children: Vec::new(),
+            }
+        })
+        .collect();
+
+    let mut sections = nest(flat);
+
+    // The untitled preamble runs only up to the first heading, whatever its
+    // level, so it is built apart from the headings rather than nesting them.
+    let first = headings[0].2;
+    if first > 0 && !text[..first].trim().is_empty() {
+        sections.insert(
+            0,
+            Section {
+                title: String::from("(Untitled)"),
+                level: 1,
+                start: 0,
+                end: first,
+                children: Vec::new(),
+            },
+        );
+    }
+
+    sections
+}
+
+fn nest(flat: Vec<Section>) -> Vec<Section> {
+    let mut roots: Vec<Section> = Vec::new();
+    let mut stack: Vec<Section> = Vec::new();
+
+    for section in flat {
+        while stack.last().is_some_and(|last| last.level >= section.level) {
+            let finished = stack.pop().unwrap();
+            match stack.last_mut() {
+                Some(parent) => parent.children.push(finished),
+                None => roots.push(finished),
+            }
+        }
+
+        stack.push(section);
+    }
+
+    while let Some(finished) = stack.pop() {
+        match stack.last_mut() {
+            Some(parent) => parent.children.push(finished),
+            None => roots.push(finished),
+        }
+    }
+
+    roots
+}
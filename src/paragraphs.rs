@@ -0,0 +1,104 @@
+use std::ops::Range;
+
+use crate::{
+    app::{PARAGRAPH_REGEX, SENTENCE_REGEX, WORD_REGEX},
+    editor::trim_range,
+};
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct ParagraphMetrics {
+    /// The position of the paragraph in the text, counting from one.
+    pub index: usize,
+    /// A byte range into the text, without surrounding whitespace.
+    pub range: Range<usize>,
+    pub words: usize,
+    pub sentences: usize,
+    /// The share of words inside quotation marks, from 0 to 1.
+    pub dialogue_ratio: f64,
+}
+
+impl ParagraphMetrics {
+    /// The average words per sentence.
+    pub fn average_sentence_length(&self) -> f64 {
+        if self.sentences == 0 {
+            return 0.0;
+        }
+
+        self.words as f64 / self.sentences as f64
+    }
+}
+
+/// The paragraphs of `text`, split the same way as for the paragraph count,
+/// without surrounding whitespace.
+pub fn ranges(text: &str) -> Vec<Range<usize>> {
+    let mut start = 0;
+    let mut paragraphs = Vec::new();
+
+    for separator in PARAGRAPH_REGEX.find_iter(text) {
+        paragraphs.push(trim_range(text, start..separator.start()));
+        start = separator.end();
+    }
+    paragraphs.push(trim_range(text, start..text.len()));
+
+    paragraphs
+}
+
+/// Measures each paragraph of `text` that has anything in it.
+pub fn analyze(text: &str) -> Vec<ParagraphMetrics> {
+    ranges(text)
+        .into_iter()
+        .filter(|range| !range.is_empty())
+        .enumerate()
+        .map(|(index, range)| {
+            let paragraph = &text[range.clone()];
+            let dialogue = dialogue(paragraph);
+            let words: Vec<usize> = WORD_REGEX
+                .find_iter(paragraph)
+                .map(|word| word.start())
+                .collect();
+            let spoken = words
+                .iter()
+                .filter(|word| dialogue.iter().any(|quote| quote.contains(word)))
+                .count();
+            let words = words.len();
+
+            ParagraphMetrics {
+                index: index + 1,
+                words,
+                sentences: SENTENCE_REGEX.find_iter(paragraph).count(),
+                dialogue_ratio: if words == 0 {
+                    0.0
+                } else {
+                    spoken as f64 / words as f64
+                },
+                range,
+            }
+        })
+        .collect()
+}
+
+/// The parts of `paragraph` inside double quotation marks. A quote left open
+/// runs to the end of the paragraph, as when speech goes on in the next one.
+/// Single quotes are left out, as they double as apostrophes.
+fn dialogue(paragraph: &str) -> Vec<Range<usize>> {
+    let mut quotes = Vec::new();
+    let mut open = None;
+
+    for (offset, char) in paragraph.char_indices() {
+        match (char, open) {
+            ('"' | '“' | '”' | '„' | '«' | '「' | '『', None) => {
+                open = Some(offset + char.len_utf8());
+            }
+            ('"' | '“' | '”' | '»' | '」' | '』', Some(start)) => {
+                quotes.push(start..offset);
+                open = None;
+            }
+            _ => {}
+        }
+    }
+    if let Some(start) = open {
+        quotes.push(start..paragraph.len());
+    }
+
+    quotes
+}
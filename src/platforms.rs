@@ -0,0 +1,201 @@
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::sync::LazyLock;
+use unicode_segmentation::UnicodeSegmentation;
+
+use crate::sms;
+
+/// What X counts a link as, whatever its length.
+const X_URL_WEIGHT: usize = 23;
+
+static URL_REGEX: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"(?i)\b(?:https?://|www\.)[^\s]+").unwrap());
+
+/// Field limits of the places that drafts are written for.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Platform {
+    X,
+    Sms,
+    GoogleTitle,
+    GoogleDescription,
+    AppStoreName,
+    AppStoreSubtitle,
+    AppStorePromotionalText,
+    AppStoreKeywords,
+    AppStoreDescription,
+    GooglePlayTitle,
+    GooglePlayShortDescription,
+    GooglePlayFullDescription,
+}
+
+/// How much of a platform's limit a text uses.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Measurement {
+    pub used: usize,
+    pub limit: usize,
+    pub unit: &'static str,
+    /// Extra detail about how the text was counted.
+    pub note: Option<String>,
+}
+
+impl Measurement {
+    pub fn remaining(&self) -> isize {
+        self.limit as isize - self.used as isize
+    }
+}
+
+impl Platform {
+    pub const ALL: [Self; 12] = [
+        Self::X,
+        Self::Sms,
+        Self::GoogleTitle,
+        Self::GoogleDescription,
+        Self::AppStoreName,
+        Self::AppStoreSubtitle,
+        Self::AppStorePromotionalText,
+        Self::AppStoreKeywords,
+        Self::AppStoreDescription,
+        Self::GooglePlayTitle,
+        Self::GooglePlayShortDescription,
+        Self::GooglePlayFullDescription,
+    ];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::X => "X Post",
+            Self::Sms => "SMS",
+            Self::GoogleTitle => "Google Title",
+            Self::GoogleDescription => "Google Meta Description",
+            Self::AppStoreName => "App Store Name",
+            Self::AppStoreSubtitle => "App Store Subtitle",
+            Self::AppStorePromotionalText => "App Store Promotional Text",
+            Self::AppStoreKeywords => "App Store Keywords",
+            Self::AppStoreDescription => "App Store Description",
+            Self::GooglePlayTitle => "Google Play Title",
+            Self::GooglePlayShortDescription => "Google Play Short Description",
+            Self::GooglePlayFullDescription => "Google Play Full Description",
+        }
+    }
+
+    pub fn measure(self, text: &str) -> Measurement {
+        let characters = |limit| Measurement {
+            used: text.chars().count(),
+            limit,
+            unit: "characters",
+            note: None,
+        };
+
+        match self {
+            Self::X => Measurement {
+                used: x_weighted_length(text),
+                limit: 280,
+                unit: "characters",
+                note: Some(format!("Links count as {X_URL_WEIGHT}")),
+            },
+            Self::Sms => {
+                let analysis = sms::analyze(text);
+                Measurement {
+                    used: analysis.units,
+                    limit: analysis.encoding.single_capacity(),
+                    unit: if analysis.encoding == sms::SmsEncoding::Gsm7 {
+                        "septets"
+                    } else {
+                        "UTF-16 units"
+                    },
+                    note: Some(format!(
+                        "{} · {} {}",
+                        analysis.encoding.label(),
+                        analysis.segments,
+                        if analysis.segments == 1 {
+                            "segment"
+                        } else {
+                            "segments"
+                        }
+                    )),
+                }
+            }
+            Self::GoogleTitle => Measurement {
+                used: pixel_width(text, 20.0),
+                limit: 600,
+                unit: "pixels",
+                note: Some(String::from("Estimated for 20px Arial")),
+            },
+            Self::GoogleDescription => Measurement {
+                used: pixel_width(text, 14.0),
+                limit: 920,
+                unit: "pixels",
+                note: Some(String::from("Estimated for 14px Arial")),
+            },
+            Self::AppStoreName | Self::AppStoreSubtitle | Self::GooglePlayTitle => characters(30),
+            Self::AppStorePromotionalText => characters(170),
+            Self::AppStoreKeywords => characters(100),
+            Self::AppStoreDescription | Self::GooglePlayFullDescription => characters(4000),
+            Self::GooglePlayShortDescription => characters(80),
+        }
+    }
+}
+
+/// The length X counts: most Latin, punctuation and symbol characters weigh
+/// one, everything else (CJK, emoji, ...) two, and each link 23. Characters
+/// are grapheme clusters, so an emoji sequence joined with ZWJ or carrying
+/// a skin tone weighs two as a whole.
+pub fn x_weighted_length(text: &str) -> usize {
+    let mut length = 0;
+    let mut last = 0;
+
+    for url in URL_REGEX.find_iter(text) {
+        length += x_weight(&text[last..url.start()]) + X_URL_WEIGHT;
+        last = url.end();
+    }
+
+    length + x_weight(&text[last..])
+}
+
+fn x_weight(text: &str) -> usize {
+    let light = |char: char| {
+        matches!(
+            char,
+            '\u{0}'..='\u{10FF}'
+                | '\u{2000}'..='\u{200D}'
+                | '\u{2010}'..='\u{201F}'
+                | '\u{2032}'..='\u{2037}'
+        )
+    };
+
+    text.graphemes(true)
+        .map(|grapheme| if grapheme.chars().all(light) { 1 } else { 2 })
+        .sum()
+}
+
+/// Approximate rendered width of `text` in Arial at `font_size` pixels,
+/// from the font's advance widths in thousandths of an em.
+pub fn pixel_width(text: &str, font_size: f64) -> usize {
+    let units: u32 = text.chars().map(arial_advance).sum();
+    (f64::from(units) * font_size / 1000.0).round() as usize
+}
+
+fn arial_advance(char: char) -> u32 {
+    match char {
+        'i' | 'j' | 'l' => 222,
+        '|' => 260,
+        ' ' | '!' | ',' | '.' | '/' | ':' | ';' | 'I' | '[' | '\\' | ']' | 'f' | 't' => 278,
+        '\'' => 191,
+        '"' => 355,
+        '(' | ')' | '-' | '`' | 'r' => 333,
+        '{' | '}' => 334,
+        '*' => 389,
+        '^' => 469,
+        'J' | 'c' | 'k' | 's' | 'v' | 'x' | 'y' | 'z' => 500,
+        '+' | '<' | '=' | '>' | '~' => 584,
+        'F' | 'T' | 'Z' => 611,
+        'A' | 'B' | 'E' | 'K' | 'P' | 'S' | 'V' | 'X' | 'Y' | '&' => 667,
+        'C' | 'D' | 'H' | 'N' | 'R' | 'U' | 'w' => 722,
+        'G' | 'O' | 'Q' => 778,
+        'M' | 'm' => 833,
+        '%' => 889,
+        'W' => 944,
+        '@' => 1015,
+        '\u{2E80}'..='\u{9FFF}' | '\u{AC00}'..='\u{D7AF}' | '\u{FF00}'..='\u{FFEF}' => 1000,
+        _ => 556,
+    }
+}
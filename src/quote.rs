@@ -0,0 +1,113 @@
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+
+use crate::{
+    app::WORD_REGEX,
+    translation::{Segment, SegmentStatus},
+};
+
+/// A file imported for a word count analysis.
+#[derive(Debug, Clone, PartialEq)]
+pub struct QuoteFile {
+    pub name: String,
+    pub segments: Vec<Segment>,
+}
+
+/// The share of the full rate charged for each kind of word, in percent.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct DiscountGrid {
+    pub new: f64,
+    pub fuzzy: f64,
+    pub repetitions: f64,
+    pub translated: f64,
+}
+
+impl Default for DiscountGrid {
+    fn default() -> Self {
+        Self {
+            new: 100.0,
+            fuzzy: 60.0,
+            repetitions: 30.0,
+            translated: 0.0,
+        }
+    }
+}
+
+/// Source words of one file by how much work they need.
+#[derive(Debug, Default, Clone, PartialEq, Serialize)]
+pub struct WordCounts {
+    pub segments: usize,
+    pub new: usize,
+    pub fuzzy: usize,
+    /// Words of untranslated segments whose source already came up earlier
+    /// in the analysis.
+    pub repetitions: usize,
+    pub translated: usize,
+}
+
+impl WordCounts {
+    pub fn total(&self) -> usize {
+        self.new + self.fuzzy + self.repetitions + self.translated
+    }
+
+    /// The words the vendor charges for once the grid's discounts apply.
+    pub fn weighted(&self, grid: &DiscountGrid) -> f64 {
+        (self.new as f64 * grid.new
+            + self.fuzzy as f64 * grid.fuzzy
+            + self.repetitions as f64 * grid.repetitions
+            + self.translated as f64 * grid.translated)
+            / 100.0
+    }
+
+    fn add(&mut self, other: &Self) {
+        self.segments += other.segments;
+        self.new += other.new;
+        self.fuzzy += other.fuzzy;
+        self.repetitions += other.repetitions;
+        self.translated += other.translated;
+    }
+}
+
+/// Counts the source words of each file, in order. A segment that still
+/// needs translating counts as a repetition when the same source, ignoring
+/// case and spacing, appeared in any earlier segment of any file.
+pub fn analyze(files: &[QuoteFile]) -> Vec<WordCounts> {
+    let mut seen = HashSet::new();
+
+    files
+        .iter()
+        .map(|file| {
+            let mut counts = WordCounts::default();
+
+            for segment in &file.segments {
+                let words = WORD_REGEX.find_iter(&segment.source).count();
+                let key = segment
+                    .source
+                    .split_whitespace()
+                    .collect::<Vec<_>>()
+                    .join(" ")
+                    .to_lowercase();
+                let repeated = !seen.insert(key);
+
+                counts.segments += 1;
+                match segment.status {
+                    SegmentStatus::Translated => counts.translated += words,
+                    _ if repeated => counts.repetitions += words,
+                    SegmentStatus::Fuzzy => counts.fuzzy += words,
+                    SegmentStatus::New => counts.new += words,
+                }
+            }
+
+            counts
+        })
+        .collect()
+}
+
+pub fn total(counts: &[WordCounts]) -> WordCounts {
+    counts
+        .iter()
+        .fold(WordCounts::default(), |mut total, counts| {
+            total.add(counts);
+            total
+        })
+}
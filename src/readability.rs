@@ -0,0 +1,114 @@
+use crate::app::{SENTENCE_REGEX, WORD_REGEX};
+
+/// Estimates the syllables in an English word by counting vowel groups,
+/// ignoring common silent endings.
+pub fn syllable_count(word: &str) -> usize {
+    let word: String = word
+        .chars()
+        .filter(|char| char.is_alphabetic())
+        .flat_map(char::to_lowercase)
+        .collect();
+
+    if word.is_empty() {
+        return 0;
+    }
+
+    if word.chars().count() <= 3 {
+        return 1;
+    }
+
+    let stem = if word.ends_with("es") || word.ends_with("ed") {
+        if word.ends_with("tes")
+            || word.ends_with("des")
+            || word.ends_with("ted")
+            || word.ends_with("ded")
+        {
+            &word[..]
+        } else {
+            &word[..word.len() - 2]
+        }
+    } else if word.ends_with('e') && !word.ends_with("le") {
+        &word[..word.len() - 1]
+    } else {
+        &word[..]
+    };
+
+    let mut syllables = 0;
+    let mut previous_vowel = false;
+
+    for char in stem.chars() {
+        let vowel = matches!(char, 'a' | 'e' | 'i' | 'o' | 'u' | 'y');
+        if vowel && !previous_vowel {
+            syllables += 1;
+        }
+        previous_vowel = vowel;
+    }
+
+    syllables.max(1)
+}
+
+/// The counts that the Flesch formulas are computed from.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct Readability {
+    pub words: usize,
+    pub sentences: usize,
+    pub syllables: usize,
+}
+
+impl Readability {
+    pub fn of(text: &str) -> Self {
+        let mut words = 0;
+        let mut syllables = 0;
+
+        for word in WORD_REGEX.find_iter(text) {
+            words += 1;
+            syllables += syllable_count(word.as_str());
+        }
+
+        let sentences = SENTENCE_REGEX
+            .find_iter(text)
+            .count()
+            .max(usize::from(words > 0));
+
+        Self {
+            words,
+            sentences,
+            syllables,
+        }
+    }
+
+    pub fn words_per_sentence(&self) -> f64 {
+        if self.sentences == 0 {
+            return 0.0;
+        }
+
+        self.words as f64 / self.sentences as f64
+    }
+
+    pub fn syllables_per_word(&self) -> f64 {
+        if self.words == 0 {
+            return 0.0;
+        }
+
+        self.syllables as f64 / self.words as f64
+    }
+
+    /// Flesch reading ease: around 60–70 is plain English, lower is harder.
+    pub fn reading_ease(&self) -> f64 {
+        if self.words == 0 {
+            return 0.0;
+        }
+
+        206.835 - 1.015 * self.words_per_sentence() - 84.6 * self.syllables_per_word()
+    }
+
+    /// Flesch–Kincaid grade level, the US school grade needed to follow the
+    /// text.
+    pub fn grade(&self) -> f64 {
+        if self.words == 0 {
+            return 0.0;
+        }
+
+        0.39 * self.words_per_sentence() + 11.8 * self.syllables_per_word() - 15.59
+    }
+}
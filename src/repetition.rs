@@ -0,0 +1,191 @@
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::{HashMap, HashSet},
+    ops::Range,
+    sync::LazyLock,
+};
+
+use crate::app::{SENTENCE_REGEX, WORD_REGEX};
+
+/// Words too common to be worth flagging when they repeat.
+pub(crate) static STOP_WORDS: LazyLock<HashSet<&str>> = LazyLock::new(|| {
+    "\
+     a about above after again against all am an and any are as at be because been before being \
+     below between both but by can could did do does doing down during each few for from \
+     further had has have having he her here hers herself him himself his how i if in into is \
+     it its itself just me more most my myself no nor not now of off on once only or other our \
+     ours ourselves out over own same she should so some such than that the their theirs them \
+     themselves then there these they this those through to too under until up very was we were \
+     what when where which while who whom why will with would you your yours"
+        .split_whitespace()
+        .collect()
+});
+/// How many words a repeated phrase has.
+const PHRASE_LENGTH: usize = 3;
+/// How many of the preceding sentences an opening is compared with.
+const OPENING_WINDOW: usize = 2;
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct RepetitionOptions {
+    /// How many words apart two uses of a word or phrase may be to count as
+    /// a repetition.
+    pub window: usize,
+    /// Whether the editor highlights the repetitions.
+    pub highlight: bool,
+}
+
+impl Default for RepetitionOptions {
+    fn default() -> Self {
+        Self {
+            window: 50,
+            highlight: false,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RepetitionKind {
+    /// The same word used again shortly after.
+    Word,
+    Phrase,
+    /// Sentences close together that start with the same word.
+    Opening,
+}
+
+impl RepetitionKind {
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::Word => "Echo word",
+            Self::Phrase => "Phrase",
+            Self::Opening => "Sentence opening",
+        }
+    }
+}
+
+/// Two uses of the same word or phrase, as byte ranges into the text.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Repetition {
+    pub kind: RepetitionKind,
+    /// The repeated word or phrase, in lowercase.
+    pub text: String,
+    pub first: Range<usize>,
+    pub second: Range<usize>,
+    /// How many words lie between the two uses, or sentences for openings.
+    pub distance: usize,
+}
+
+/// Finds words and phrases repeated within `window` words of each other,
+/// and sentences starting like one of the sentences just before them, in
+/// the order they occur. Words that are part of a repeated phrase are not
+/// reported again on their own.
+pub fn find(text: &str, window: usize) -> Vec<Repetition> {
+    let words: Vec<(String, Range<usize>)> = WORD_REGEX
+        .find_iter(text)
+        .map(|word| (word.as_str().to_lowercase(), word.range()))
+        .collect();
+    let is_stop_word = |word: &str| STOP_WORDS.contains(word);
+
+    let mut repetitions: Vec<Repetition> = Vec::new();
+    // Which words belong to a repeated phrase, by index.
+    let mut in_phrase = vec![false; words.len()];
+    let mut last_phrase: HashMap<Vec<&str>, usize> = HashMap::new();
+    // Where the latest phrase match started, first and second use.
+    let mut last_match = None;
+
+    for start in 0..words.len().saturating_sub(PHRASE_LENGTH - 1) {
+        let phrase: Vec<&str> = words[start..start + PHRASE_LENGTH]
+            .iter()
+            .map(|(word, _)| word.as_str())
+            .collect();
+        if phrase.iter().all(|word| is_stop_word(word)) {
+            continue;
+        }
+
+        if let Some(previous) = last_phrase.insert(phrase.clone(), start) {
+            // Overlapping matches such as "a a a a" are not repetitions.
+            let distance = start.checked_sub(previous + PHRASE_LENGTH);
+            if let Some(distance) = distance.filter(|distance| *distance <= window) {
+                let end = |start: usize| words[start + PHRASE_LENGTH - 1].1.end;
+
+                // A longer repeated phrase continues the match that started
+                // one word earlier in both places, as long as its two uses
+                // stay apart.
+                let continues = previous > 0
+                    && last_match == Some((previous - 1, start - 1))
+                    && repetitions
+                        .last()
+                        .is_some_and(|repetition| end(previous) <= repetition.second.start);
+
+                if continues {
+                    let repetition = repetitions.last_mut().unwrap();
+                    repetition.text.push(' ');
+                    repetition.text.push_str(phrase[PHRASE_LENGTH - 1]);
+                    repetition.first.end = end(previous);
+                    repetition.second.end = end(start);
+                } else if in_phrase[previous] && in_phrase[start] {
+                    continue;
+                } else {
+                    repetitions.push(Repetition {
+                        kind: RepetitionKind::Phrase,
+                        text: phrase.join(" "),
+                        first: words[previous].1.start..end(previous),
+                        second: words[start].1.start..end(start),
+                        distance,
+                    });
+                }
+                last_match = Some((previous, start));
+                in_phrase[previous..previous + PHRASE_LENGTH].fill(true);
+                in_phrase[start..start + PHRASE_LENGTH].fill(true);
+            }
+        }
+    }
+
+    let mut last_word: HashMap<&str, usize> = HashMap::new();
+
+    for (index, (word, range)) in words.iter().enumerate() {
+        if is_stop_word(word) || word.chars().all(|char| char.is_numeric()) {
+            continue;
+        }
+
+        if let Some(previous) = last_word.insert(word, index) {
+            let distance = index - previous - 1;
+            if distance <= window && !(in_phrase[previous] && in_phrase[index]) {
+                repetitions.push(Repetition {
+                    kind: RepetitionKind::Word,
+                    text: word.clone(),
+                    first: words[previous].1.clone(),
+                    second: range.clone(),
+                    distance,
+                });
+            }
+        }
+    }
+
+    let openings: Vec<(String, Range<usize>)> = SENTENCE_REGEX
+        .find_iter(text)
+        .filter_map(|sentence| {
+            let word = WORD_REGEX.find(sentence.as_str())?;
+            let start = sentence.start() + word.start();
+            Some((word.as_str().to_lowercase(), start..start + word.len()))
+        })
+        .collect();
+
+    for (index, (word, range)) in openings.iter().enumerate() {
+        let earlier = index.saturating_sub(OPENING_WINDOW)..index;
+        if let Some(previous) = earlier
+            .rev()
+            .find(|previous| openings[*previous].0 == *word)
+        {
+            repetitions.push(Repetition {
+                kind: RepetitionKind::Opening,
+                text: word.clone(),
+                first: openings[previous].1.clone(),
+                second: range.clone(),
+                distance: index - previous - 1,
+            });
+        }
+    }
+
+    repetitions.sort_by_key(|repetition| (repetition.second.start, repetition.first.start));
+    repetitions
+}
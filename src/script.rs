@@ -0,0 +1,144 @@
+use regex::Regex;
+use std::{sync::LazyLock, time::Duration};
+
+/// Seconds for a `[pause]` or `[beat]` without an explicit length.
+const DEFAULT_PAUSE_SECONDS: f64 = 2.0;
+const DEFAULT_BEAT_SECONDS: f64 = 1.0;
+/// The longest a single pause marker can last, so that a typo such as an
+/// extra run of digits cannot stall the timings.
+const MAX_PAUSE_SECONDS: f64 = 3600.0;
+
+static SLIDE_SEPARATOR_REGEX: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"(?m)^[ \t]*-{3,}[ \t]*$").unwrap());
+static HEADING_REGEX: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"(?m)^ {0,3}#{1,6}[ \t]+(.*?)[ \t#]*$").unwrap());
+/// A bracketed span, with the `(` or `[` after it so that links can be told
+/// apart from cues.
+static CUE_REGEX: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"\[([^\]\n]*)\]([(\[])?").unwrap());
+static PAUSE_REGEX: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"(?i)^\s*(pause|beat)(?:\s+(\d+(?:\.\d+)?)\s*(ms|s|secs?|seconds?)?)?\s*$").unwrap()
+});
+
+/// A stretch of a script between slide separators or headings. Offsets are
+/// byte offsets into the editor text.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Slide {
+    pub title: String,
+    pub start: usize,
+    pub end: usize,
+    /// The text with pause markers and stage directions removed.
+    pub spoken: String,
+    /// Seconds of explicit pauses.
+    pub pause: f64,
+    pub directions: usize,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct SlideTiming {
+    pub slide: Slide,
+    pub words: usize,
+    pub duration: Duration,
+    /// When the slide starts, counted from the start of the talk.
+    pub starts_at: Duration,
+}
+
+/// Splits a script at `---` separators and `#` headings, leaving out
+/// bracketed stage directions and collecting `[pause]`, `[beat]`,
+/// `[pause 3s]` and `[beat 500ms]` markers.
+pub fn slides(text: &str) -> Vec<Slide> {
+    let mut boundaries: Vec<(usize, usize)> = SLIDE_SEPARATOR_REGEX
+        .find_iter(text)
+        .map(|separator| (separator.start(), separator.end()))
+        .chain(
+            HEADING_REGEX
+                .find_iter(text)
+                .map(|heading| (heading.start(), heading.start())),
+        )
+        .collect();
+    boundaries.sort_unstable();
+
+    let mut ranges = Vec::new();
+    let mut start = 0;
+    for (end, next) in boundaries {
+        ranges.push((start, end));
+        start = next;
+    }
+    ranges.push((start, text.len()));
+
+    ranges
+        .into_iter()
+        .filter(|(start, end)| !text[*start..*end].trim().is_empty())
+        .enumerate()
+        .map(|(index, (start, end))| slide(&text[start..end], index, start, end))
+        .collect()
+}
+
+fn slide(source: &str, index: usize, start: usize, end: usize) -> Slide {
+    let mut pause = 0.0;
+    let mut directions = 0;
+
+    let spoken = CUE_REGEX
+        .replace_all(source, |captures: &regex::Captures| {
+            if is_markup(captures) {
+                return captures[0].to_owned();
+            }
+
+            match PAUSE_REGEX.captures(&captures[1]) {
+                Some(marker) => pause += pause_seconds(&marker),
+                None => directions += 1,
+            }
+            " ".to_owned()
+        })
+        .into_owned();
+
+    let title = HEADING_REGEX
+        .captures(source)
+        .map(|heading| heading[1].to_owned())
+        .or_else(|| {
+            spoken
+                .lines()
+                .map(str::trim)
+                .find(|line| !line.is_empty())
+                .map(|line| line.chars().take(40).collect())
+        })
+        .unwrap_or_else(|| format!("Slide {}", index + 1));
+
+    Slide {
+        title,
+        start,
+        end,
+        spoken,
+        pause,
+        directions,
+    }
+}
+
+/// Whether a bracketed span is Markdown rather than a cue: a link or image
+/// followed by its `(url)` or `[ref]`, a `[^1]` footnote or a `[ ]` / `[x]`
+/// task box.
+fn is_markup(captures: &regex::Captures) -> bool {
+    let inner = &captures[1];
+
+    captures.get(2).is_some() || inner.starts_with('^') || matches!(inner, " " | "x" | "X")
+}
+
+fn pause_seconds(marker: &regex::Captures) -> f64 {
+    let Some(length) = marker
+        .get(2)
+        .and_then(|length| length.as_str().parse::<f64>().ok())
+    else {
+        return if marker[1].eq_ignore_ascii_case("beat") {
+            DEFAULT_BEAT_SECONDS
+        } else {
+            DEFAULT_PAUSE_SECONDS
+        };
+    };
+
+    let seconds = match marker.get(3).map(|unit| unit.as_str().to_lowercase()) {
+        Some(unit) if unit == "ms" => length / 1000.0,
+        _ => length,
+    };
+
+    seconds.min(MAX_PAUSE_SECONDS)
+}
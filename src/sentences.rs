@@ -0,0 +1,91 @@
+use std::{cmp::Ordering, ops::Range};
+
+use crate::{app::SENTENCE_REGEX, characters::CharacterUnit, readability::Readability};
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct SentenceMetrics {
+    /// The position of the sentence in the text, counting from one.
+    pub index: usize,
+    /// A byte range into the text, without surrounding whitespace.
+    pub range: Range<usize>,
+    pub words: usize,
+    pub characters: usize,
+    pub syllables: usize,
+    /// The Flesch–Kincaid grade of the sentence on its own.
+    pub grade: f64,
+}
+
+/// Measures each sentence of `text`, counting characters in `unit`.
+pub fn analyze(text: &str, unit: CharacterUnit) -> Vec<SentenceMetrics> {
+    SENTENCE_REGEX
+        .find_iter(text)
+        .enumerate()
+        .map(|(index, sentence)| {
+            let trimmed = sentence.as_str().trim();
+            let start = sentence.start() + (sentence.len() - sentence.as_str().trim_start().len());
+            let readability = Readability::of(trimmed);
+
+            SentenceMetrics {
+                index: index + 1,
+                range: start..start + trimmed.len(),
+                words: readability.words,
+                characters: unit.count(trimmed),
+                syllables: readability.syllables,
+                grade: readability.grade(),
+            }
+        })
+        .collect()
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum SentenceColumn {
+    #[default]
+    Index,
+    Words,
+    Characters,
+    Syllables,
+    Grade,
+}
+
+impl SentenceColumn {
+    pub const ALL: [Self; 5] = [
+        Self::Index,
+        Self::Words,
+        Self::Characters,
+        Self::Syllables,
+        Self::Grade,
+    ];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::Index => "#",
+            Self::Words => "Words",
+            Self::Characters => "Chars",
+            Self::Syllables => "Syll.",
+            Self::Grade => "Grade",
+        }
+    }
+
+    fn compare(self, a: &SentenceMetrics, b: &SentenceMetrics) -> Ordering {
+        match self {
+            Self::Index => a.index.cmp(&b.index),
+            Self::Words => a.words.cmp(&b.words),
+            Self::Characters => a.characters.cmp(&b.characters),
+            Self::Syllables => a.syllables.cmp(&b.syllables),
+            Self::Grade => a.grade.total_cmp(&b.grade),
+        }
+    }
+}
+
+/// Sorts `sentences` by `column`, keeping ties in text order.
+pub fn sort(sentences: &mut [SentenceMetrics], column: SentenceColumn, descending: bool) {
+    sentences.sort_by(|a, b| {
+        let ordering = column.compare(a, b);
+        if descending {
+            ordering.reverse()
+        } else {
+            ordering
+        }
+        .then_with(|| a.index.cmp(&b.index))
+    });
+}
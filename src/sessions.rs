@@ -0,0 +1,149 @@
+use js_sys::Date;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use wasm_bindgen::JsValue;
+
+use crate::storage::Storage;
+
+/// Edits further apart than this start a new session.
+pub const SESSION_GAP: f64 = 30.0 * 60.0 * 1000.0;
+/// Older sessions are dropped beyond this many; daily totals are kept.
+const MAX_SESSIONS: usize = 500;
+
+const STORAGE_KEY: &str = "writing_log";
+
+/// A run of edits without a long pause. Times are milliseconds since the
+/// Unix epoch.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct Session {
+    pub start: f64,
+    pub end: f64,
+    /// Words added minus words removed.
+    pub words: i64,
+}
+
+impl Session {
+    pub fn is_active(&self, now: f64) -> bool {
+        now - self.end < SESSION_GAP
+    }
+
+    /// Net words per hour, counting at least a minute so that the first
+    /// edits of a session do not produce absurd rates.
+    pub fn words_per_hour(&self) -> f64 {
+        let hours = (self.end - self.start).max(60_000.0) / 3_600_000.0;
+        self.words as f64 / hours
+    }
+}
+
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+pub struct WritingLog {
+    /// Net words written per local `YYYY-MM-DD` day.
+    pub days: BTreeMap<String, i64>,
+    pub sessions: Vec<Session>,
+}
+
+impl WritingLog {
+    /// Adds the change in word count from one edit made at `now`.
+    pub fn record(&mut self, words: i64, now: f64) {
+        *self.days.entry(day_key(&date(now))).or_default() += words;
+
+        match self.sessions.last_mut() {
+            Some(session) if session.is_active(now) => {
+                session.end = now;
+                session.words += words;
+            }
+            _ => self.sessions.push(Session {
+                start: now,
+                end: now,
+                words,
+            }),
+        }
+
+        let excess = self.sessions.len().saturating_sub(MAX_SESSIONS);
+        self.sessions.drain(..excess);
+    }
+
+    pub fn words_on(&self, day: &str) -> i64 {
+        self.days.get(day).copied().unwrap_or_default()
+    }
+
+    pub fn active_session(&self, now: f64) -> Option<&Session> {
+        self.sessions
+            .last()
+            .filter(|session| session.is_active(now))
+    }
+
+    /// Consecutive days with words written, ending today or, if nothing has
+    /// been written yet today, yesterday.
+    pub fn current_streak(&self, now: f64) -> usize {
+        let mut day = date(now);
+        if self.words_on(&day_key(&day)) <= 0 {
+            day = previous_day(&day);
+        }
+
+        let mut streak = 0;
+        while self.words_on(&day_key(&day)) > 0 {
+            streak += 1;
+            day = previous_day(&day);
+        }
+
+        streak
+    }
+
+    pub fn longest_streak(&self) -> usize {
+        let mut longest = 0;
+        let mut streak = 0;
+        let mut expected = None;
+
+        for (day, _) in self.days.iter().filter(|(_, words)| **words > 0) {
+            streak = if expected.as_ref() == Some(day) {
+                streak + 1
+            } else {
+                1
+            };
+            longest = longest.max(streak);
+
+            let date = Date::new(&JsValue::from_str(&format!("{day}T12:00:00")));
+            expected = Some(day_key(&next_day(&date)));
+        }
+
+        longest
+    }
+}
+
+pub fn date(milliseconds: f64) -> Date {
+    Date::new(&JsValue::from_f64(milliseconds))
+}
+
+/// The local calendar day of `date` as `YYYY-MM-DD`.
+pub fn day_key(date: &Date) -> String {
+    format!(
+        "{:04}-{:02}-{:02}",
+        date.get_full_year(),
+        date.get_month() + 1,
+        date.get_date()
+    )
+}
+
+pub fn previous_day(date: &Date) -> Date {
+    let previous = Date::new(date);
+    previous.set_date(date.get_date() - 1);
+    previous
+}
+
+pub fn next_day(date: &Date) -> Date {
+    let next = Date::new(date);
+    next.set_date(date.get_date() + 1);
+    next
+}
+
+pub fn load(storage: &Storage) -> WritingLog {
+    storage
+        .get_item(STORAGE_KEY)
+        .and_then(|s| serde_json::from_str(&s).ok())
+        .unwrap_or_default()
+}
+
+pub fn save(storage: &Storage, log: &WritingLog) {
+    storage.set_item(STORAGE_KEY, &serde_json::to_string(log).unwrap());
+}
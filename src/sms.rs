@@ -0,0 +1,120 @@
+/// Characters of the GSM 03.38 default alphabet, one septet each.
+const GSM_BASIC: &str = "@£$¥èéùìòÇ\nØø\rÅåΔ_ΦΓΛΩΠΨΣΘΞÆæßÉ !\"#¤%&'()*+,-./0123456789:;<=>?¡ABCDEFGHIJKLMNOPQRSTUVWXYZÄÖÑÜ§¿abcdefghijklmnopqrstuvwxyzäöñüà";
+/// Characters of the extension table, sent as an escape and a septet.
+const GSM_EXTENSION: &str = "\u{c}^{}\\[~]|€";
+
+const GSM_SINGLE: usize = 160;
+const GSM_PART: usize = 153;
+const UCS2_SINGLE: usize = 70;
+const UCS2_PART: usize = 67;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SmsEncoding {
+    Gsm7,
+    Ucs2,
+}
+
+impl SmsEncoding {
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::Gsm7 => "GSM-7",
+            Self::Ucs2 => "UCS-2",
+        }
+    }
+
+    /// Units that fit in a message sent as one segment.
+    pub fn single_capacity(self) -> usize {
+        match self {
+            Self::Gsm7 => GSM_SINGLE,
+            Self::Ucs2 => UCS2_SINGLE,
+        }
+    }
+
+    /// Units that fit in each segment of a concatenated message, the rest
+    /// being taken by the concatenation header.
+    pub fn part_capacity(self) -> usize {
+        match self {
+            Self::Gsm7 => GSM_PART,
+            Self::Ucs2 => UCS2_PART,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SmsAnalysis {
+    pub encoding: SmsEncoding,
+    /// Septets for GSM-7, UTF-16 code units for UCS-2.
+    pub units: usize,
+    pub segments: usize,
+    /// Units still free in the last segment.
+    pub remaining: usize,
+    /// The distinct characters outside the GSM-7 alphabets, in order of
+    /// appearance.
+    pub ucs2_characters: Vec<char>,
+}
+
+/// Septets `char` takes in GSM-7, or `None` if it is not in either table.
+fn gsm_septets(char: char) -> Option<usize> {
+    if GSM_BASIC.contains(char) {
+        Some(1)
+    } else if GSM_EXTENSION.contains(char) {
+        Some(2)
+    } else {
+        None
+    }
+}
+
+pub fn analyze(text: &str) -> SmsAnalysis {
+    let mut ucs2_characters = Vec::new();
+    for char in text.chars() {
+        if gsm_septets(char).is_none() && !ucs2_characters.contains(&char) {
+            ucs2_characters.push(char);
+        }
+    }
+
+    let encoding = if ucs2_characters.is_empty() {
+        SmsEncoding::Gsm7
+    } else {
+        SmsEncoding::Ucs2
+    };
+
+    let cost = |char: char| match encoding {
+        SmsEncoding::Gsm7 => gsm_septets(char).unwrap_or(1),
+        SmsEncoding::Ucs2 => char.len_utf16(),
+    };
+
+    let units: usize = text.chars().map(cost).sum();
+
+    if units <= encoding.single_capacity() {
+        return SmsAnalysis {
+            encoding,
+            units,
+            segments: usize::from(units > 0),
+            remaining: encoding.single_capacity() - units,
+            ucs2_characters,
+        };
+    }
+
+    // Escape sequences and surrogate pairs cannot be split between
+    // segments, so fill them character by character.
+    let part = encoding.part_capacity();
+    let mut segments = 1;
+    let mut used = 0;
+
+    for char in text.chars() {
+        let cost = cost(char);
+        if used + cost > part {
+            segments += 1;
+            used = 0;
+        }
+        used += cost;
+    }
+
+    SmsAnalysis {
+        encoding,
+        units,
+        segments,
+        remaining: part - used,
+        ucs2_characters,
+    }
+}
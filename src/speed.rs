@@ -0,0 +1,133 @@
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+
+use crate::components::calculate_duration;
+
+/// The longest pause, in seconds, a profile can add per sentence or paragraph.
+pub const MAX_PAUSE: f64 = 3600.0;
+
+/// A named pace for turning a text into a duration.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SpeedProfile {
+    pub name: String,
+    /// Words per minute, or characters per minute for hand writing.
+    pub rate: u32,
+    /// Seconds added for every sentence.
+    pub sentence_pause: f64,
+    /// Seconds added for every paragraph.
+    pub paragraph_pause: f64,
+}
+
+impl SpeedProfile {
+    fn preset(name: &str, rate: u32, sentence_pause: f64, paragraph_pause: f64) -> Self {
+        Self {
+            name: name.to_owned(),
+            rate,
+            sentence_pause,
+            paragraph_pause,
+        }
+    }
+
+    /// A profile for a speed that was entered before profiles existed.
+    pub fn custom(rate: u32) -> Self {
+        Self::preset("Custom", rate, 0.0, 0.0)
+    }
+
+    /// The time for `units` words or characters split into `sentences` and
+    /// `paragraphs`.
+    pub fn duration(&self, units: usize, sentences: usize, paragraphs: usize) -> Duration {
+        let pause = |pause: f64| pause.clamp(0.0, MAX_PAUSE);
+        let pauses = sentences as f64 * pause(self.sentence_pause)
+            + paragraphs.saturating_sub(1) as f64 * pause(self.paragraph_pause);
+
+        calculate_duration(units, self.rate.max(1))
+            + Duration::try_from_secs_f64(pauses).unwrap_or_default()
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Activity {
+    Reading,
+    Speaking,
+    HandWriting,
+}
+
+impl Activity {
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::Reading => "Reading",
+            Self::Speaking => "Speaking",
+            Self::HandWriting => "Hand Writing",
+        }
+    }
+
+    pub fn unit(self) -> &'static str {
+        match self {
+            Self::Reading | Self::Speaking => "words per minute",
+            Self::HandWriting => "characters per minute",
+        }
+    }
+
+    /// The built-in profiles, the first being the default.
+    pub fn presets(self) -> Vec<SpeedProfile> {
+        match self {
+            Self::Reading => vec![
+                SpeedProfile::preset("Average reader", 275, 0.0, 0.0),
+                SpeedProfile::preset("Slow reader", 200, 0.0, 0.0),
+                SpeedProfile::preset("Fast reader", 400, 0.0, 0.0),
+                SpeedProfile::preset("Skimming", 700, 0.0, 0.0),
+            ],
+            Self::Speaking => vec![
+                SpeedProfile::preset("Conversation", 180, 0.0, 0.0),
+                SpeedProfile::preset("Presentation", 130, 0.5, 2.0),
+                SpeedProfile::preset("Audiobook narration", 155, 0.4, 1.5),
+                SpeedProfile::preset("Radio news", 170, 0.3, 1.0),
+            ],
+            Self::HandWriting => vec![
+                SpeedProfile::preset("Average hand writing", 68, 0.0, 0.0),
+                SpeedProfile::preset("Careful hand writing", 45, 0.0, 0.0),
+                SpeedProfile::preset("Note taking", 100, 0.0, 0.0),
+            ],
+        }
+    }
+
+    pub fn default_profile(self) -> SpeedProfile {
+        self.presets().remove(0)
+    }
+}
+
+/// The profile chosen for each activity in a document.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SpeedProfiles {
+    pub reading: SpeedProfile,
+    pub speaking: SpeedProfile,
+    pub hand_writing: SpeedProfile,
+}
+
+impl Default for SpeedProfiles {
+    fn default() -> Self {
+        Self {
+            reading: Activity::Reading.default_profile(),
+            speaking: Activity::Speaking.default_profile(),
+            hand_writing: Activity::HandWriting.default_profile(),
+        }
+    }
+}
+
+impl SpeedProfiles {
+    pub fn get(&self, activity: Activity) -> &SpeedProfile {
+        match activity {
+            Activity::Reading => &self.reading,
+            Activity::Speaking => &self.speaking,
+            Activity::HandWriting => &self.hand_writing,
+        }
+    }
+
+    pub fn get_mut(&mut self, activity: Activity) -> &mut SpeedProfile {
+        match activity {
+            Activity::Reading => &mut self.reading,
+            Activity::Speaking => &mut self.speaking,
+            Activity::HandWriting => &mut self.hand_writing,
+        }
+    }
+}
@@ -0,0 +1,425 @@
+use js_sys::{Array, Function, Promise};
+use leptos::{spawn_local, window};
+use std::{cell::RefCell, collections::HashMap, fmt, future::Future, pin::Pin, rc::Rc};
+use wasm_bindgen::{closure::Closure, JsCast, JsValue};
+use wasm_bindgen_futures::JsFuture;
+use web_sys::{DomException, IdbDatabase, IdbOpenDbRequest, IdbRequest, IdbTransactionMode};
+
+const DATABASE_NAME: &str = "simple-word-counter";
+const OBJECT_STORE: &str = "entries";
+const MIGRATED_KEY: &str = "migrated_from_local_storage";
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum StorageError {
+    /// The backend cannot be used at all, e.g. storage is disabled in
+    /// private browsing mode.
+    Unavailable(String),
+    QuotaExceeded,
+    Failed(String),
+}
+
+impl fmt::Display for StorageError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Unavailable(reason) => write!(f, "storage is unavailable ({reason})"),
+            Self::QuotaExceeded => write!(f, "the storage quota has been exceeded"),
+            Self::Failed(reason) => write!(f, "{reason}"),
+        }
+    }
+}
+
+impl From<JsValue> for StorageError {
+    fn from(value: JsValue) -> Self {
+        match value.dyn_ref::<DomException>() {
+            Some(exception) if exception.name() == "QuotaExceededError" => Self::QuotaExceeded,
+            Some(exception) => Self::Failed(exception.message()),
+            None => Self::Failed(
+                value
+                    .as_string()
+                    .unwrap_or_else(|| String::from("unknown storage error")),
+            ),
+        }
+    }
+}
+
+pub type StorageFuture<'a, T> = Pin<Box<dyn Future<Output = Result<T, StorageError>> + 'a>>;
+
+/// A persistent key-value store. Reads happen once at startup through
+/// [`StorageBackend::load`]; afterwards [`Storage`] serves them from memory.
+pub trait StorageBackend {
+    fn name(&self) -> &'static str;
+    fn load(&self) -> StorageFuture<'_, HashMap<String, String>>;
+    fn set(&self, key: String, value: String) -> StorageFuture<'_, ()>;
+    fn remove(&self, key: String) -> StorageFuture<'_, ()>;
+}
+
+#[derive(Debug, Default)]
+pub struct MemoryBackend {
+    entries: RefCell<HashMap<String, String>>,
+}
+
+impl StorageBackend for MemoryBackend {
+    fn name(&self) -> &'static str {
+        "memory"
+    }
+
+    fn load(&self) -> StorageFuture<'_, HashMap<String, String>> {
+        Box::pin(async move { Ok(self.entries.borrow().clone()) })
+    }
+
+    fn set(&self, key: String, value: String) -> StorageFuture<'_, ()> {
+        self.entries.borrow_mut().insert(key, value);
+        Box::pin(async { Ok(()) })
+    }
+
+    fn remove(&self, key: String) -> StorageFuture<'_, ()> {
+        self.entries.borrow_mut().remove(&key);
+        Box::pin(async { Ok(()) })
+    }
+}
+
+pub struct LocalStorageBackend {
+    storage: web_sys::Storage,
+}
+
+impl LocalStorageBackend {
+    pub fn new() -> Result<Self, StorageError> {
+        window()
+            .local_storage()
+            .map_err(StorageError::from)?
+            .map(|storage| Self { storage })
+            .ok_or_else(|| StorageError::Unavailable(String::from("localStorage is disabled")))
+    }
+
+    fn entries(&self) -> Result<HashMap<String, String>, StorageError> {
+        let mut entries = HashMap::new();
+
+        for index in 0..self.storage.length()? {
+            if let Some(key) = self.storage.key(index)? {
+                if let Some(value) = self.storage.get_item(&key)? {
+                    entries.insert(key, value);
+                }
+            }
+        }
+
+        Ok(entries)
+    }
+}
+
+impl StorageBackend for LocalStorageBackend {
+    fn name(&self) -> &'static str {
+        "localStorage"
+    }
+
+    fn load(&self) -> StorageFuture<'_, HashMap<String, String>> {
+        Box::pin(async move { self.entries() })
+    }
+
+    fn set(&self, key: String, value: String) -> StorageFuture<'_, ()> {
+        Box::pin(async move { Ok(self.storage.set_item(&key, &value)?) })
+    }
+
+    fn remove(&self, key: String) -> StorageFuture<'_, ()> {
+        Box::pin(async move { Ok(self.storage.remove_item(&key)?) })
+    }
+}
+
+pub struct IndexedDbBackend {
+    database: IdbDatabase,
+}
+
+impl IndexedDbBackend {
+    pub async fn open() -> Result<Self, StorageError> {
+        let factory = window()
+            .indexed_db()?
+            .ok_or_else(|| StorageError::Unavailable(String::from("IndexedDB is disabled")))?;
+        let request = factory.open_with_u32(DATABASE_NAME, 1)?;
+
+        let upgrade_request = request.clone();
+        let on_upgrade_needed = Closure::<dyn FnMut()>::new(move || {
+            if let Ok(database) = upgrade_request.result() {
+                let database = database.unchecked_into::<IdbDatabase>();
+                if !database.object_store_names().contains(OBJECT_STORE) {
+                    database.create_object_store(OBJECT_STORE).ok();
+                }
+            }
+        });
+        request.set_onupgradeneeded(Some(on_upgrade_needed.as_ref().unchecked_ref()));
+
+        let database = request_result(&request).await?;
+        drop(on_upgrade_needed);
+
+        Ok(Self {
+            database: database.unchecked_into(),
+        })
+    }
+
+    async fn write(&self, key: String, value: Option<String>) -> Result<(), StorageError> {
+        let transaction = self
+            .database
+            .transaction_with_str_and_mode(OBJECT_STORE, IdbTransactionMode::Readwrite)?;
+        let store = transaction.object_store(OBJECT_STORE)?;
+        let key = JsValue::from_str(&key);
+
+        let request = match value {
+            Some(value) => store.put_with_key(&JsValue::from_str(&value), &key)?,
+            None => store.delete(&key)?,
+        };
+
+        request_result(&request).await.map(|_| ())
+    }
+}
+
+impl StorageBackend for IndexedDbBackend {
+    fn name(&self) -> &'static str {
+        "IndexedDB"
+    }
+
+    fn load(&self) -> StorageFuture<'_, HashMap<String, String>> {
+        Box::pin(async move {
+            let transaction = self.database.transaction_with_str(OBJECT_STORE)?;
+            let store = transaction.object_store(OBJECT_STORE)?;
+            // Both requests go out before either is awaited, as the
+            // transaction commits once it has no requests left.
+            let keys = request_result(&store.get_all_keys()?);
+            let values = request_result(&store.get_all()?);
+            let (keys, values) = (keys.await?, values.await?);
+
+            Ok(Array::from(&keys)
+                .iter()
+                .zip(Array::from(&values).iter())
+                .filter_map(|(key, value)| Some((key.as_string()?, value.as_string()?)))
+                .collect())
+        })
+    }
+
+    fn set(&self, key: String, value: String) -> StorageFuture<'_, ()> {
+        Box::pin(self.write(key, Some(value)))
+    }
+
+    fn remove(&self, key: String) -> StorageFuture<'_, ()> {
+        Box::pin(self.write(key, None))
+    }
+}
+
+/// Resolves once `request` succeeds, with its result. The handlers are
+/// attached at once, so several requests of one transaction can be issued
+/// before any of them is awaited. An open request that is blocked by a
+/// connection in another tab fails instead of waiting.
+fn request_result(request: &IdbRequest) -> impl Future<Output = Result<JsValue, StorageError>> {
+    let mut on_success = None;
+    let mut on_error = None;
+    let mut on_blocked = None;
+
+    let promise = Promise::new(&mut |resolve: Function, reject: Function| {
+        let success_request = request.clone();
+        let success = Closure::<dyn FnMut()>::new(move || {
+            let result = success_request.result().unwrap_or(JsValue::UNDEFINED);
+            resolve.call1(&JsValue::NULL, &result).ok();
+        });
+
+        let error_request = request.clone();
+        let error_reject = reject.clone();
+        let error = Closure::<dyn FnMut()>::new(move || {
+            let error = error_request
+                .error()
+                .ok()
+                .flatten()
+                .map_or(JsValue::UNDEFINED, JsValue::from);
+            error_reject.call1(&JsValue::NULL, &error).ok();
+        });
+
+        if let Some(open_request) = request.dyn_ref::<IdbOpenDbRequest>() {
+            let blocked = Closure::<dyn FnMut()>::new(move || {
+                let reason = JsValue::from_str("the database is in use by another tab");
+                reject.call1(&JsValue::NULL, &reason).ok();
+            });
+            open_request.set_onblocked(Some(blocked.as_ref().unchecked_ref()));
+            on_blocked = Some(blocked);
+        }
+
+        request.set_onsuccess(Some(success.as_ref().unchecked_ref()));
+        request.set_onerror(Some(error.as_ref().unchecked_ref()));
+        on_success = Some(success);
+        on_error = Some(error);
+    });
+
+    let request = request.clone();
+    async move {
+        let result = JsFuture::from(promise).await;
+        request.set_onsuccess(None);
+        request.set_onerror(None);
+        if let Some(open_request) = request.dyn_ref::<IdbOpenDbRequest>() {
+            open_request.set_onblocked(None);
+        }
+        drop((on_success, on_error, on_blocked));
+
+        Ok(result?)
+    }
+}
+
+type ErrorHandler = Rc<dyn Fn(StorageError)>;
+
+/// The app's persistent settings and documents. Everything is loaded into
+/// memory when the app starts, so reads are synchronous; writes update the
+/// memory copy at once and are flushed to the backend in the background.
+#[derive(Clone)]
+pub struct Storage {
+    backend: Rc<dyn StorageBackend>,
+    entries: Rc<RefCell<HashMap<String, String>>>,
+    on_error: Rc<RefCell<Option<ErrorHandler>>>,
+    pending_errors: Rc<RefCell<Vec<StorageError>>>,
+}
+
+impl Storage {
+    /// Opens the best available backend: IndexedDB, then localStorage, then
+    /// an in-memory store that keeps the app usable without persistence.
+    pub async fn open() -> Self {
+        let mut errors = Vec::new();
+
+        match IndexedDbBackend::open().await {
+            Ok(backend) => match Self::with_backend(Rc::new(backend)).await {
+                Ok(storage) => {
+                    storage.migrate_local_storage().await;
+                    return storage.with_errors(errors);
+                }
+                Err(error) => errors.push(error),
+            },
+            Err(error) => errors.push(error),
+        }
+
+        match LocalStorageBackend::new() {
+            Ok(backend) => match Self::with_backend(Rc::new(backend)).await {
+                Ok(storage) => return storage.with_errors(errors),
+                Err(error) => errors.push(error),
+            },
+            Err(error) => errors.push(error),
+        }
+
+        errors.push(StorageError::Unavailable(String::from(
+            "changes will be lost when the page is closed",
+        )));
+
+        Self::with_backend(Rc::new(MemoryBackend::default()))
+            .await
+            .unwrap()
+            .with_errors(errors)
+    }
+
+    pub async fn with_backend(backend: Rc<dyn StorageBackend>) -> Result<Self, StorageError> {
+        let entries = backend.load().await?;
+
+        Ok(Self {
+            backend,
+            entries: Rc::new(RefCell::new(entries)),
+            on_error: Rc::default(),
+            pending_errors: Rc::default(),
+        })
+    }
+
+    fn with_errors(self, errors: Vec<StorageError>) -> Self {
+        self.pending_errors.borrow_mut().extend(errors);
+        self
+    }
+
+    pub fn backend_name(&self) -> &'static str {
+        self.backend.name()
+    }
+
+    /// Reports failed writes to `handler`, starting with any problems met
+    /// while opening the storage.
+    pub fn on_error(&self, handler: impl Fn(StorageError) + 'static) {
+        let handler: ErrorHandler = Rc::new(handler);
+
+        for error in self.pending_errors.borrow_mut().drain(..) {
+            handler(error);
+        }
+
+        *self.on_error.borrow_mut() = Some(handler);
+    }
+
+    pub fn get_item(&self, key: &str) -> Option<String> {
+        self.entries.borrow().get(key).cloned()
+    }
+
+    pub fn set_item(&self, key: &str, value: &str) {
+        if self.get_item(key).as_deref() == Some(value) {
+            return;
+        }
+
+        self.entries
+            .borrow_mut()
+            .insert(key.to_owned(), value.to_owned());
+
+        let storage = self.clone();
+        let (key, value) = (key.to_owned(), value.to_owned());
+        spawn_local(async move {
+            let result = storage.backend.set(key, value).await;
+            storage.report(result);
+        });
+    }
+
+    pub fn remove_item(&self, key: &str) {
+        if self.entries.borrow_mut().remove(key).is_none() {
+            return;
+        }
+
+        let storage = self.clone();
+        let key = key.to_owned();
+        spawn_local(async move {
+            let result = storage.backend.remove(key).await;
+            storage.report(result);
+        });
+    }
+
+    pub fn keys(&self) -> Vec<String> {
+        self.entries.borrow().keys().cloned().collect()
+    }
+
+    fn report(&self, result: Result<(), StorageError>) {
+        let Err(error) = result else {
+            return;
+        };
+
+        let handler = self.on_error.borrow().clone();
+        match handler {
+            Some(handler) => handler(error),
+            None => self.pending_errors.borrow_mut().push(error),
+        }
+    }
+
+    /// Copies keys written by earlier versions of the app to localStorage
+    /// into this store, then removes them from localStorage.
+    async fn migrate_local_storage(&self) {
+        if self.get_item(MIGRATED_KEY).is_some() {
+            return;
+        }
+
+        let Ok(local_storage) = LocalStorageBackend::new() else {
+            return;
+        };
+        let Ok(legacy) = local_storage.entries() else {
+            return;
+        };
+
+        for (key, value) in &legacy {
+            if let Err(error) = self.backend.set(key.clone(), value.clone()).await {
+                self.pending_errors.borrow_mut().push(error);
+                return;
+            }
+
+            self.entries.borrow_mut().insert(key.clone(), value.clone());
+        }
+
+        if self
+            .backend
+            .set(MIGRATED_KEY.to_owned(), String::from("true"))
+            .await
+            .is_ok()
+        {
+            for key in legacy.into_keys() {
+                local_storage.remove(key).await.ok();
+            }
+        }
+    }
+}
@@ -0,0 +1,138 @@
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::{sync::LazyLock, time::Duration};
+
+use crate::app::WORD_REGEX;
+
+static TIMING_REGEX: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"^(?:(\d+):)?(\d{1,2}):(\d{2})[,.](\d{1,3})\s*-->\s*(?:(\d+):)?(\d{1,2}):(\d{2})[,.](\d{1,3})").unwrap()
+});
+static TAG_REGEX: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"<[^>]*>|\{\\[^}]*\}").unwrap());
+
+/// A single timed caption from an SRT or WebVTT file.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Cue {
+    pub start: Duration,
+    pub end: Duration,
+    pub text: String,
+}
+
+impl Cue {
+    pub fn duration(&self) -> Duration {
+        self.end.saturating_sub(self.start)
+    }
+
+    pub fn word_count(&self) -> usize {
+        WORD_REGEX.find_iter(&self.text).count()
+    }
+
+    /// Characters the viewer has to read, line breaks excluded.
+    pub fn character_count(&self) -> usize {
+        self.text.chars().filter(|char| *char != '\n').count()
+    }
+
+    pub fn words_per_minute(&self) -> f64 {
+        rate(self.word_count(), self.duration()) * 60.0
+    }
+
+    pub fn characters_per_second(&self) -> f64 {
+        rate(self.character_count(), self.duration())
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct ReadingSpeedLimits {
+    pub max_characters_per_second: f64,
+    pub max_words_per_minute: f64,
+}
+
+impl Default for ReadingSpeedLimits {
+    fn default() -> Self {
+        Self {
+            max_characters_per_second: 17.0,
+            max_words_per_minute: 200.0,
+        }
+    }
+}
+
+impl ReadingSpeedLimits {
+    pub fn exceeded_by(&self, cue: &Cue) -> bool {
+        cue.characters_per_second() > self.max_characters_per_second
+            || cue.words_per_minute() > self.max_words_per_minute
+    }
+}
+
+/// Parses SRT and WebVTT sources. Blocks without a timing line (the
+/// `WEBVTT` header, `NOTE` and `STYLE` blocks) are skipped.
+pub fn parse(source: &str) -> Vec<Cue> {
+    let source = source.trim_start_matches('\u{feff}').replace("\r\n", "\n");
+    let mut cues = Vec::new();
+
+    for block in source.split("\n\n") {
+        let mut lines = block
+            .lines()
+            .skip_while(|line| !TIMING_REGEX.is_match(line.trim()));
+
+        let Some(timing) = lines
+            .next()
+            .and_then(|line| TIMING_REGEX.captures(line.trim()))
+        else {
+            continue;
+        };
+
+        let timestamp = |offset: usize| {
+            let part = |index: usize| {
+                timing
+                    .get(offset + index)
+                    .map_or(0, |m| m.as_str().parse::<u64>().unwrap_or_default())
+            };
+            let millis = timing.get(offset + 4).map_or(0, |m| {
+                format!("{:0<3}", m.as_str())
+                    .parse::<u64>()
+                    .unwrap_or_default()
+            });
+
+            Duration::from_millis(((part(1) * 60 + part(2)) * 60 + part(3)) * 1000 + millis)
+        };
+
+        let text = lines
+            .map(|line| TAG_REGEX.replace_all(line, "").trim().to_owned())
+            .filter(|line| !line.is_empty())
+            .collect::<Vec<_>>()
+            .join("\n");
+
+        cues.push(Cue {
+            start: timestamp(0),
+            end: timestamp(4),
+            text,
+        });
+    }
+
+    cues
+}
+
+/// The spoken text of all cues, one cue per line.
+pub fn transcript(cues: &[Cue]) -> String {
+    cues.iter()
+        .map(|cue| cue.text.replace('\n', " "))
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+pub fn total_words_per_minute(cues: &[Cue]) -> f64 {
+    let words = cues.iter().map(Cue::word_count).sum();
+    rate(words, cues.iter().map(Cue::duration).sum()) * 60.0
+}
+
+pub fn total_characters_per_second(cues: &[Cue]) -> f64 {
+    let characters = cues.iter().map(Cue::character_count).sum();
+    rate(characters, cues.iter().map(Cue::duration).sum())
+}
+
+fn rate(count: usize, duration: Duration) -> f64 {
+    if duration.is_zero() {
+        return 0.0;
+    }
+
+    count as f64 / duration.as_secs_f64()
+}
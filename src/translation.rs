@@ -0,0 +1,504 @@
+use roxmltree::{Document, Node, ParsingOptions};
+use serde::{Deserialize, Serialize};
+
+use crate::{app::WORD_REGEX, characters::CharacterUnit};
+
+/// Inline elements that stand for formatting codes rather than text.
+const CODE_ELEMENTS: [&str; 4] = ["ph", "bpt", "ept", "it"];
+/// The most plural forms a gettext language has, as Arabic does; higher
+/// `msgstr[N]` indices are ignored.
+const MAX_PLURAL_FORMS: usize = 6;
+
+/// A source text and its translation.
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Translation {
+    pub source_language: String,
+    pub target_language: String,
+    pub segments: Vec<Segment>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Segment {
+    pub id: String,
+    pub source: String,
+    pub target: String,
+    #[serde(default)]
+    pub status: SegmentStatus,
+}
+
+/// How far a segment is from a finished translation, as vendors quote it.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum SegmentStatus {
+    #[default]
+    New,
+    /// Translated, but marked as needing review.
+    Fuzzy,
+    Translated,
+}
+
+impl SegmentStatus {
+    /// The status of a segment whose file does not say.
+    fn of(target: &str) -> Self {
+        if target.is_empty() {
+            Self::New
+        } else {
+            Self::Translated
+        }
+    }
+
+    /// XLIFF 1.2 `state` values.
+    fn from_xliff_1(state: Option<&str>, approved: bool, target: &str) -> Self {
+        match state {
+            _ if approved => Self::Translated,
+            Some("new" | "needs-translation") => Self::New,
+            Some(state) if state.starts_with("needs-") => Self::Fuzzy,
+            Some("translated" | "signed-off" | "final") => Self::Translated,
+            _ => Self::of(target),
+        }
+    }
+
+    /// XLIFF 2.0 `state` values, where a translation still in its initial
+    /// state has not been confirmed.
+    fn from_xliff_2(state: Option<&str>, target: &str) -> Self {
+        match state {
+            Some("initial") if !target.is_empty() => Self::Fuzzy,
+            Some("initial") => Self::New,
+            Some("translated" | "reviewed" | "final") => Self::Translated,
+            _ => Self::of(target),
+        }
+    }
+}
+
+impl Segment {
+    /// How many times longer the translation is than the source, or `None`
+    /// while either is empty.
+    pub fn expansion(&self, unit: CharacterUnit) -> Option<f64> {
+        let source = unit.count(&self.source);
+        let target = unit.count(&self.target);
+
+        (source > 0 && target > 0).then(|| target as f64 / source as f64)
+    }
+
+    pub fn exceeds(&self, unit: CharacterUnit, max_expansion: f64) -> bool {
+        self.expansion(unit)
+            .is_some_and(|expansion| expansion > max_expansion)
+    }
+}
+
+/// Totals for one side of a translation.
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub struct LanguageTotals {
+    pub words: usize,
+    pub characters: usize,
+}
+
+impl Translation {
+    /// Pairs pasted texts line by line, ignoring blank lines.
+    pub fn pair(source: &str, target: &str) -> Vec<Segment> {
+        let lines = |text: &str| {
+            text.lines()
+                .map(str::trim)
+                .filter(|line| !line.is_empty())
+                .map(str::to_owned)
+                .collect::<Vec<_>>()
+        };
+        let mut sources = lines(source).into_iter();
+        let mut targets = lines(target).into_iter();
+        let mut segments = Vec::new();
+
+        loop {
+            let (source, target) = match (sources.next(), targets.next()) {
+                (None, None) => break,
+                (source, target) => (source.unwrap_or_default(), target.unwrap_or_default()),
+            };
+
+            segments.push(Segment {
+                id: (segments.len() + 1).to_string(),
+                status: SegmentStatus::of(&target),
+                source,
+                target,
+            });
+        }
+
+        segments
+    }
+
+    /// Reads a gettext catalog if `file_name` is a `.po` or `.pot` file, or
+    /// else a TMX or XLIFF file.
+    pub fn import(file_name: &str, contents: &str) -> Result<Self, String> {
+        let file_name = file_name.to_lowercase();
+        if !file_name.ends_with(".po") && !file_name.ends_with(".pot") {
+            return Self::parse(contents);
+        }
+
+        let translation = parse_po(contents);
+        if translation.segments.is_empty() {
+            return Err(String::from("The catalog has no messages"));
+        }
+
+        Ok(translation)
+    }
+
+    /// Reads a TMX or an XLIFF 1.2 or 2.0 file.
+    pub fn parse(xml: &str) -> Result<Self, String> {
+        // TMX exports usually declare the TMX DTD, which the default options
+        // reject.
+        let options = ParsingOptions {
+            allow_dtd: true,
+            ..ParsingOptions::default()
+        };
+        let document =
+            Document::parse_with_options(xml, options).map_err(|error| error.to_string())?;
+        let root = document.root_element();
+
+        let translation = match root.tag_name().name() {
+            "tmx" => parse_tmx(root),
+            "xliff" => parse_xliff(root),
+            name => return Err(format!("<{name}> is not a TMX or XLIFF document")),
+        };
+
+        if translation.segments.is_empty() {
+            return Err(String::from("The file has no translation units"));
+        }
+
+        Ok(translation)
+    }
+
+    pub fn source_totals(&self, unit: CharacterUnit) -> LanguageTotals {
+        totals(self.segments.iter().map(|segment| &segment.source), unit)
+    }
+
+    pub fn target_totals(&self, unit: CharacterUnit) -> LanguageTotals {
+        totals(self.segments.iter().map(|segment| &segment.target), unit)
+    }
+
+    /// The expansion of the whole translation, or `None` while either side
+    /// is empty.
+    pub fn expansion(&self, unit: CharacterUnit) -> Option<f64> {
+        let source = self.source_totals(unit).characters;
+        let target = self.target_totals(unit).characters;
+
+        (source > 0 && target > 0).then(|| target as f64 / source as f64)
+    }
+
+    /// Joins the sources, or the targets, one segment per line.
+    pub fn lines(&self, target: bool) -> String {
+        self.segments
+            .iter()
+            .map(|segment| {
+                if target {
+                    segment.target.as_str()
+                } else {
+                    segment.source.as_str()
+                }
+            })
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+}
+
+fn totals<'a>(texts: impl Iterator<Item = &'a String>, unit: CharacterUnit) -> LanguageTotals {
+    texts.fold(LanguageTotals::default(), |totals, text| LanguageTotals {
+        words: totals.words + WORD_REGEX.find_iter(text).count(),
+        characters: totals.characters + unit.count(text),
+    })
+}
+
+/// Each `<tu>` holds one `<tuv>` per language. The source is the variant in
+/// the header's `srclang`, or the first one, and the target is the first
+/// variant in another language.
+fn parse_tmx(root: Node) -> Translation {
+    let mut source_language = root
+        .children()
+        .find(|node| node.has_tag_name("header"))
+        .and_then(|header| header.attribute("srclang"))
+        .filter(|language| *language != "*all*")
+        .unwrap_or_default()
+        .to_owned();
+    let mut target_language = String::new();
+    let mut segments = Vec::new();
+
+    for unit in root.descendants().filter(|node| node.has_tag_name("tu")) {
+        let variants: Vec<(String, String)> = unit
+            .children()
+            .filter(|node| node.has_tag_name("tuv"))
+            .map(|variant| {
+                let language = variant
+                    .attribute((roxmltree::NS_XML_URI, "lang"))
+                    .or_else(|| variant.attribute("lang"))
+                    .unwrap_or_default()
+                    .to_owned();
+                let text = child_text(variant, "seg").unwrap_or_default();
+                (language, text)
+            })
+            .collect();
+
+        let source = variants
+            .iter()
+            .position(|(language, _)| language.eq_ignore_ascii_case(&source_language))
+            .unwrap_or(0);
+        if source_language.is_empty() {
+            if let Some((language, _)) = variants.get(source) {
+                source_language.clone_from(language);
+            }
+        }
+
+        let target = variants
+            .iter()
+            .enumerate()
+            .position(|(index, (language, _))| {
+                index != source && !language.eq_ignore_ascii_case(&source_language)
+            });
+        if let (true, Some(target)) = (target_language.is_empty(), target) {
+            target_language.clone_from(&variants[target].0);
+        }
+
+        let target = target
+            .map(|target| variants[target].1.clone())
+            .unwrap_or_default();
+        segments.push(Segment {
+            id: unit
+                .attribute("tuid")
+                .map_or_else(|| (segments.len() + 1).to_string(), str::to_owned),
+            source: variants
+                .get(source)
+                .map(|(_, text)| text.clone())
+                .unwrap_or_default(),
+            status: SegmentStatus::of(&target),
+            target,
+        });
+    }
+
+    Translation {
+        source_language,
+        target_language,
+        segments,
+    }
+}
+
+/// XLIFF 1.2 keeps a `<source>` and `<target>` in each `<trans-unit>`,
+/// XLIFF 2.0 in each `<segment>` of a `<unit>`.
+fn parse_xliff(root: Node) -> Translation {
+    let file = root.children().find(|node| node.has_tag_name("file"));
+    let language = |version_2: &str, version_1: &str| {
+        root.attribute(version_2)
+            .or_else(|| file.and_then(|file| file.attribute(version_1)))
+            .unwrap_or_default()
+            .to_owned()
+    };
+    let mut segments = Vec::new();
+
+    for unit in root.descendants() {
+        if unit.has_tag_name("trans-unit") {
+            let target = child_text(unit, "target").unwrap_or_default();
+            let state = unit
+                .children()
+                .find(|node| node.has_tag_name("target"))
+                .and_then(|node| node.attribute("state"));
+
+            segments.push(Segment {
+                id: unit.attribute("id").unwrap_or_default().to_owned(),
+                source: child_text(unit, "source").unwrap_or_default(),
+                status: SegmentStatus::from_xliff_1(
+                    state,
+                    unit.attribute("approved") == Some("yes"),
+                    &target,
+                ),
+                target,
+            });
+        } else if unit.has_tag_name("unit") {
+            let parts: Vec<Node> = unit
+                .children()
+                .filter(|node| node.has_tag_name("segment"))
+                .collect();
+
+            for (index, segment) in parts.iter().enumerate() {
+                let id = unit.attribute("id").unwrap_or_default();
+                let target = child_text(*segment, "target").unwrap_or_default();
+                segments.push(Segment {
+                    id: if parts.len() > 1 {
+                        format!("{id}.{}", index + 1)
+                    } else {
+                        id.to_owned()
+                    },
+                    source: child_text(*segment, "source").unwrap_or_default(),
+                    status: SegmentStatus::from_xliff_2(segment.attribute("state"), &target),
+                    target,
+                });
+            }
+        }
+    }
+
+    Translation {
+        source_language: language("srcLang", "source-language"),
+        target_language: language("trgLang", "target-language"),
+        segments,
+    }
+}
+
+/// The part of a gettext entry that a continued string belongs to.
+#[derive(Clone, Copy)]
+enum PoField {
+    Context,
+    Id,
+    Plural,
+    Str(usize),
+}
+
+#[derive(Default)]
+struct PoEntry {
+    context: Option<String>,
+    id: Option<String>,
+    plural: Option<String>,
+    strings: Vec<String>,
+    fuzzy: bool,
+}
+
+/// Reads a gettext catalog. Obsolete entries are skipped, and plural
+/// messages count as their singular form and its first translation.
+fn parse_po(text: &str) -> Translation {
+    let mut translation = Translation::default();
+    let mut entry = PoEntry::default();
+    let mut field = None;
+
+    for line in text.lines().map(str::trim).chain([""]) {
+        let starts_entry = line.is_empty()
+            || line.starts_with('#')
+            || line.starts_with("msgctxt")
+            || line.starts_with("msgid ");
+        if starts_entry && !entry.strings.is_empty() {
+            finish_po_entry(&mut translation, std::mem::take(&mut entry));
+            field = None;
+        }
+
+        if line.starts_with("#~") {
+            continue;
+        } else if let Some(flags) = line.strip_prefix("#,") {
+            entry.fuzzy |= flags.split(',').any(|flag| flag.trim() == "fuzzy");
+            continue;
+        } else if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+
+        let (keyword, value) = match line.find('"') {
+            Some(quote) => (line[..quote].trim(), unquote(&line[quote..])),
+            None => continue,
+        };
+
+        field = match keyword {
+            "" => field,
+            "msgctxt" => Some(PoField::Context),
+            "msgid" => Some(PoField::Id),
+            "msgid_plural" => Some(PoField::Plural),
+            "msgstr" => Some(PoField::Str(0)),
+            keyword => keyword
+                .strip_prefix("msgstr[")
+                .and_then(|index| index.strip_suffix(']'))
+                .and_then(|index| index.parse().ok())
+                .filter(|index| *index < MAX_PLURAL_FORMS)
+                .map(PoField::Str),
+        };
+
+        match field {
+            Some(PoField::Context) => entry
+                .context
+                .get_or_insert_with(String::new)
+                .push_str(&value),
+            Some(PoField::Id) => entry.id.get_or_insert_with(String::new).push_str(&value),
+            Some(PoField::Plural) => entry
+                .plural
+                .get_or_insert_with(String::new)
+                .push_str(&value),
+            Some(PoField::Str(index)) => {
+                if entry.strings.len() <= index {
+                    entry.strings.resize(index + 1, String::new());
+                }
+                entry.strings[index].push_str(&value);
+            }
+            None => {}
+        }
+    }
+
+    translation
+}
+
+fn finish_po_entry(translation: &mut Translation, entry: PoEntry) {
+    let Some(id) = entry.id else {
+        return;
+    };
+    let target = entry.strings.first().cloned().unwrap_or_default();
+
+    // The entry without a message id is the catalog's header.
+    if id.is_empty() {
+        if let Some(language) = target
+            .lines()
+            .find_map(|line| line.strip_prefix("Language:"))
+        {
+            translation.target_language = language.trim().to_owned();
+        }
+        return;
+    }
+
+    let collapse = |text: &str| text.split_whitespace().collect::<Vec<_>>().join(" ");
+    let target = collapse(&target);
+    let status = match SegmentStatus::of(&target) {
+        SegmentStatus::Translated if entry.fuzzy => SegmentStatus::Fuzzy,
+        status => status,
+    };
+
+    translation.segments.push(Segment {
+        id: entry
+            .context
+            .unwrap_or_else(|| (translation.segments.len() + 1).to_string()),
+        source: collapse(&id),
+        target,
+        status,
+    });
+}
+
+/// The contents of a C-style quoted string.
+fn unquote(quoted: &str) -> String {
+    let inner = quoted
+        .strip_prefix('"')
+        .and_then(|quoted| quoted.strip_suffix('"'))
+        .unwrap_or(quoted);
+    let mut text = String::new();
+    let mut chars = inner.chars();
+
+    while let Some(char) = chars.next() {
+        if char != '\\' {
+            text.push(char);
+            continue;
+        }
+
+        match chars.next() {
+            Some('n') => text.push('\n'),
+            Some('t') => text.push('\t'),
+            Some('r') => {}
+            Some(char) => text.push(char),
+            None => {}
+        }
+    }
+
+    text
+}
+
+/// The text of the first child element called `name`, with formatting codes
+/// left out and whitespace collapsed.
+fn child_text(node: Node, name: &str) -> Option<String> {
+    let child = node.children().find(|child| child.has_tag_name(name))?;
+    let mut text = String::new();
+    collect_text(child, &mut text);
+
+    Some(text.split_whitespace().collect::<Vec<_>>().join(" "))
+}
+
+fn collect_text(node: Node, text: &mut String) {
+    for child in node.children() {
+        if child.is_text() {
+            text.push_str(child.text().unwrap_or_default());
+        } else if child.is_element() && !CODE_ELEMENTS.contains(&child.tag_name().name()) {
+            collect_text(child, text);
+        }
+    }
+}
@@ -0,0 +1,80 @@
+use js_sys::Date;
+use serde::{Deserialize, Serialize};
+
+use crate::{storage::Storage, subtitles::Cue};
+
+/// Oldest entries are dropped once the stack holds more text than this, so
+/// that it fits comfortably next to the documents in storage.
+const MAX_BYTES: usize = 1_000_000;
+const MAX_ENTRIES: usize = 20;
+
+const STORAGE_KEY: &str = "undo";
+
+/// The text of a document before an action replaced it.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct UndoEntry {
+    /// What was done, e.g. "Cleared input".
+    pub label: String,
+    /// Milliseconds since the Unix epoch.
+    pub created: f64,
+    pub document: u64,
+    pub text: String,
+    #[serde(default)]
+    pub subtitles: Vec<Cue>,
+}
+
+impl UndoEntry {
+    pub fn new(label: String, document: u64, text: String, subtitles: Vec<Cue>) -> Self {
+        Self {
+            label,
+            created: Date::now(),
+            document,
+            text,
+            subtitles,
+        }
+    }
+
+    fn size(&self) -> usize {
+        self.text.len()
+            + self
+                .subtitles
+                .iter()
+                .map(|cue| cue.text.len())
+                .sum::<usize>()
+    }
+}
+
+/// Pushes `entry`, dropping the oldest entries beyond the size limits. The
+/// newest entry is always kept, however large.
+pub fn push(stack: &mut Vec<UndoEntry>, entry: UndoEntry) {
+    stack.push(entry);
+
+    let mut total: usize = stack.iter().map(UndoEntry::size).sum();
+    let mut excess = stack.len().saturating_sub(MAX_ENTRIES);
+    let keep_from = stack
+        .iter()
+        .take(stack.len() - 1)
+        .take_while(|entry| {
+            if excess > 0 || total > MAX_BYTES {
+                excess = excess.saturating_sub(1);
+                total -= entry.size();
+                true
+            } else {
+                false
+            }
+        })
+        .count();
+
+    stack.drain(..keep_from);
+}
+
+pub fn load(storage: &Storage) -> Vec<UndoEntry> {
+    storage
+        .get_item(STORAGE_KEY)
+        .and_then(|s| serde_json::from_str(&s).ok())
+        .unwrap_or_default()
+}
+
+pub fn save(storage: &Storage, stack: &[UndoEntry]) {
+    storage.set_item(STORAGE_KEY, &serde_json::to_string(stack).unwrap());
+}